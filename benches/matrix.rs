@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use reed_solomon::galois::GaloisField;
+use reed_solomon::matrix::Matrix;
+use reed_solomon::ReedSolomon;
+
+/// Decoding a 128+4 code with 4 missing data shards inverts the 128x128
+/// sub matrix of the encoding matrix made of the rows of the present
+/// shards, i.e. the columns of the generator matrix.
+fn bench_invert_128x128(c: &mut Criterion) {
+    let rs = ReedSolomon::new(128, 4).unwrap();
+    let generator = rs.generator_matrix().into_data();
+    let columns: Vec<Vec<u8>> = (4..132)
+        .map(|c| generator.iter().map(|row| row[c]).collect())
+        .collect();
+    let rows: Vec<&[u8]> = columns.iter().map(|x| x.as_slice()).collect();
+    let matrix = Matrix::from_rows(&rows).unwrap();
+    let gf8 = GaloisField::new();
+
+    c.bench_function("invert 128x128", |b| {
+        b.iter(|| black_box(&matrix).checked_invert(gf8).unwrap())
+    });
+}
+
+/// Creating a 32+224 code builds a 256x32 Vandermonde matrix.
+fn bench_new_vandermonde_256x32(c: &mut Criterion) {
+    c.bench_function("vandermonde 256x32", |b| {
+        b.iter(|| ReedSolomon::new(black_box(32), black_box(224)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_invert_128x128, bench_new_vandermonde_256x32);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use reed_solomon::ReedSolomon;
+
+/// Rebuilding the parity shards of a 10+4 code. `decode` uses the first
+/// 10 present shards and inverts the sub matrix even though they are the
+/// data shards, `reconstruct_cheapest` only recomputes the parity.
+fn bench_missing_parity_10_4(c: &mut Criterion) {
+    let rs = ReedSolomon::new(10, 4).unwrap();
+    let mut shards: Vec<Vec<u8>> = (0..14).map(|i| vec![i as u8; 4096]).collect();
+    shards = rs.encode(shards).unwrap();
+    for shard in shards.iter_mut().skip(10) {
+        shard.clear();
+    }
+
+    let mut group = c.benchmark_group("missing parity 10+4");
+    group.bench_function("first-k", |b| {
+        b.iter(|| rs.decode(black_box(shards.clone())).unwrap())
+    });
+    group.bench_function("data-preferred", |b| {
+        b.iter(|| rs.reconstruct_cheapest(black_box(shards.clone())).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_missing_parity_10_4);
+criterion_main!(benches);
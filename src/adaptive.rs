@@ -0,0 +1,154 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+
+/// A Struct to encode stripes with different no. of parity shards
+/// depending on how important the data of the stripe is.
+pub struct AdaptiveEncoder {
+    levels: Vec<(u8, ReedSolomon)>,
+}
+
+impl AdaptiveEncoder {
+    /// Create a new AdaptiveEncoder from the given levels. Every level is
+    /// a minimum importance and the code used for stripes whose importance
+    /// is at least that minimum (and below the next level).
+    /// # Arguments
+    ///
+    /// * `levels` - Pairs of minimum importance and the code for that importance
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::adaptive::AdaptiveEncoder;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let low = ReedSolomon::new(4, 1).unwrap();
+    /// let high = ReedSolomon::new(4, 4).unwrap();
+    /// let encoder = AdaptiveEncoder::new(vec![(0, low), (128, high)]);
+    /// ```
+    pub fn new(levels: Vec<(u8, ReedSolomon)>) -> AdaptiveEncoder {
+        let mut levels = levels;
+        levels.sort_by_key(|(min_importance, _)| *min_importance);
+
+        AdaptiveEncoder { levels }
+    }
+
+    /// Returns the code used for stripes of the given importance i.e. the
+    /// code of the highest level whose minimum importance is not above it.
+    /// # Arguments
+    ///
+    /// * `importance` - Importance of the stripe
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::adaptive::AdaptiveEncoder;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let low = ReedSolomon::new(4, 1).unwrap();
+    /// let high = ReedSolomon::new(4, 4).unwrap();
+    /// let encoder = AdaptiveEncoder::new(vec![(0, low), (128, high)]);
+    /// let rs = encoder.code(200);
+    /// ```
+    pub fn code(&self, importance: u8) -> Result<&ReedSolomon, Error> {
+        match self
+            .levels
+            .iter()
+            .rev()
+            .find(|(min_importance, _)| *min_importance <= importance)
+        {
+            Some((_, rs)) => Ok(rs),
+            None => Err(Error::NoCodeForImportance(importance)),
+        }
+    }
+
+    /// Encodes checksum shards for a stripe of data shards using the
+    /// code matching the importance of the stripe.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `importance` - Importance of the stripe
+    /// * `data` - Data shards of the stripe
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::adaptive::AdaptiveEncoder;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let low = ReedSolomon::new(2, 1).unwrap();
+    /// let high = ReedSolomon::new(2, 3).unwrap();
+    /// let encoder = AdaptiveEncoder::new(vec![(0, low), (128, high)]);
+    /// let shards = encoder.encode_stripe(200, &[vec![0, 1, 2], vec![3, 4, 5]]);
+    /// ```
+    pub fn encode_stripe(&self, importance: u8, data: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Error> {
+        let rs = self.code(importance)?;
+        if data.len() != rs.data_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+
+        let shard_elem_len = data.first().map_or(0, |shard| shard.len());
+        let mut shards = data.to_vec();
+        shards.resize(rs.total_shard_count, vec![0; shard_elem_len]);
+
+        rs.encode(shards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_stripe() {
+        let low = match ReedSolomon::new(4, 1) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let high = match ReedSolomon::new(4, 3) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let encoder = AdaptiveEncoder::new(vec![(128, high), (0, low)]);
+        let data = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+
+        let low_shards = match encoder.encode_stripe(10, &data) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let high_shards = match encoder.encode_stripe(200, &data) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(5, low_shards.len());
+        assert_eq!(7, high_shards.len());
+        assert!(high_shards.len() > low_shards.len());
+        assert_eq!(data[..], high_shards[..4]);
+
+        let mut broken_shards = high_shards.clone();
+        broken_shards[0] = vec![];
+        broken_shards[2] = vec![];
+        broken_shards[3] = vec![];
+        let rs = match encoder.code(200) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let decoded_shard = match rs.decode(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(high_shards, decoded_shard);
+
+        assert!(encoder.encode_stripe(10, &data[..3]).is_err());
+    }
+    #[test]
+    fn test_code_without_matching_level() {
+        let rs = match ReedSolomon::new(4, 1) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let encoder = AdaptiveEncoder::new(vec![(10, rs)]);
+
+        assert!(matches!(
+            encoder.code(5),
+            Err(Error::NoCodeForImportance(5))
+        ));
+        assert!(encoder.code(10).is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::ReedSolomon;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// Alignment in bytes of the start of every ShardBuffer.
+pub const SHARD_ALIGNMENT: usize = 64;
+
+/// A Struct to hold a shard whose first byte is aligned to
+/// `SHARD_ALIGNMENT` bytes, as needed by SIMD kernels. It is not enough
+/// for O_DIRECT I/O, which needs the buffers aligned to the logical block
+/// size of the device, usually 512 or 4096 bytes.
+/// It can be used as a shard wherever `AsRef<[u8]>` and `AsMut<[u8]>`
+/// shards are taken, e.g. by `encode_in_place` and `verify`.
+pub struct ShardBuffer {
+    // Over-allocated by up to SHARD_ALIGNMENT - 1 bytes, so that an
+    // aligned window of the shard length fits in it. It is never grown,
+    // so the window doesn't move.
+    data: Vec<u8>,
+    offset: usize,
+    len: usize,
+}
+
+impl ShardBuffer {
+    /// Create a new zeroed ShardBuffer of the given length.
+    /// # Arguments
+    ///
+    /// * `len` - Length of the shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::buffer::{ShardBuffer, SHARD_ALIGNMENT};
+    ///
+    /// let shard = ShardBuffer::new(100);
+    /// assert_eq!(100, shard.len());
+    /// assert_eq!(0, shard.as_ptr() as usize % SHARD_ALIGNMENT);
+    /// ```
+    pub fn new(len: usize) -> ShardBuffer {
+        let data = vec![0; len + SHARD_ALIGNMENT - 1];
+        let offset = (SHARD_ALIGNMENT - data.as_ptr() as usize % SHARD_ALIGNMENT) % SHARD_ALIGNMENT;
+
+        ShardBuffer { data, offset, len }
+    }
+
+    /// Create a new ShardBuffer holding a copy of the given shard.
+    /// # Arguments
+    ///
+    /// * `shard` - Bytes of the shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::buffer::ShardBuffer;
+    ///
+    /// let shard = ShardBuffer::from_slice(&[0, 1, 2]);
+    /// assert_eq!(&[0, 1, 2], &shard[..]);
+    /// ```
+    pub fn from_slice(shard: &[u8]) -> ShardBuffer {
+        let mut buffer = ShardBuffer::new(shard.len());
+        buffer.copy_from_slice(shard);
+
+        buffer
+    }
+}
+
+impl Deref for ShardBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data[self.offset..self.offset + self.len]
+    }
+}
+
+impl DerefMut for ShardBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.data[self.offset..self.offset + self.len]
+    }
+}
+
+impl AsRef<[u8]> for ShardBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsMut<[u8]> for ShardBuffer {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl Clone for ShardBuffer {
+    // The copy is at another address, so its offset is recomputed.
+    fn clone(&self) -> Self {
+        ShardBuffer::from_slice(self)
+    }
+}
+
+impl fmt::Debug for ShardBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for ShardBuffer {
+    fn eq(&self, other: &ShardBuffer) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Eq for ShardBuffer {}
+
+impl From<Vec<u8>> for ShardBuffer {
+    fn from(shard: Vec<u8>) -> Self {
+        ShardBuffer::from_slice(&shard)
+    }
+}
+
+impl From<ShardBuffer> for Vec<u8> {
+    fn from(shard: ShardBuffer) -> Self {
+        shard.to_vec()
+    }
+}
+
+impl ReedSolomon {
+    /// Returns zeroed, aligned buffers for all the shards of the code, each
+    /// of the given length, ready to be filled with data and encoded.
+    /// # Arguments
+    ///
+    /// * `len` - Length of every shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.alloc_shards(3);
+    /// shards[0].copy_from_slice(&[0, 1, 2]);
+    /// shards[1].copy_from_slice(&[3, 4, 5]);
+    /// rs.encode_in_place(&mut shards).unwrap();
+    /// assert_eq!(&[5, 14, 11], &shards[3][..]);
+    /// ```
+    pub fn alloc_shards(&self, len: usize) -> Vec<ShardBuffer> {
+        (0..self.total_shard_count)
+            .map(|_| ShardBuffer::new(len))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_buffer_alignment() {
+        for len in [0, 1, 63, 64, 65, 1000] {
+            let shard = ShardBuffer::new(len);
+            assert_eq!(len, shard.len());
+            assert_eq!(0, shard.as_ptr() as usize % SHARD_ALIGNMENT);
+            assert!(shard.iter().all(|&x| x == 0));
+
+            let copy = shard.clone();
+            assert_eq!(0, copy.as_ptr() as usize % SHARD_ALIGNMENT);
+            assert_eq!(shard, copy);
+        }
+    }
+
+    #[test]
+    fn test_alloc_shards_round_trip() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = rs.alloc_shards(4);
+        for (i, shard) in shards[..3].iter_mut().enumerate() {
+            shard.copy_from_slice(&[i as u8, 1, 2, 3]);
+        }
+        if let Err(e) = rs.encode_in_place(&mut shards) {
+            panic!("{}", e);
+        }
+
+        let mut broken: Vec<Option<ShardBuffer>> = shards.iter().cloned().map(Some).collect();
+        broken[0] = None;
+        broken[4] = None;
+        match rs.reconstruct(&mut broken) {
+            Ok(_) => assert_eq!(shards, broken.into_iter().flatten().collect::<Vec<_>>()),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use crate::cache::{DecodeCache, DEFAULT_DECODE_CACHE_SIZE};
+use crate::error::Error;
+use crate::galois::{GaloisField, Kernel, FIELD_SIZE};
+use crate::kernel::{FullTableKernel, MulKernel};
+use crate::matrix::Matrix;
+use crate::ReedSolomon;
+use std::sync::{Arc, Mutex};
+
+/// The kind of matrix the encoding matrix is built from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MatrixKind {
+    /// A Vandermonde matrix, which is the default.
+    Vandermonde,
+    /// A Cauchy matrix, as used by Jerasure and some Ceph profiles.
+    Cauchy,
+}
+
+/// A Struct to configure and create a Reed Solomon Erasure Coding.
+pub struct ReedSolomonBuilder {
+    data_shards: usize,
+    parity_shards: usize,
+    systematic: bool,
+    matrix_kind: MatrixKind,
+    decode_cache_size: usize,
+    kernel: Option<Arc<dyn MulKernel + Send + Sync>>,
+    force_scalar: bool,
+}
+
+impl ReedSolomonBuilder {
+    /// Create a new builder with the default configuration i.e. a
+    /// systematic code.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let builder = ReedSolomonBuilder::new(4, 2);
+    /// ```
+    pub fn new(data_shards: usize, parity_shards: usize) -> ReedSolomonBuilder {
+        ReedSolomonBuilder {
+            data_shards,
+            parity_shards,
+            systematic: true,
+            matrix_kind: MatrixKind::Vandermonde,
+            decode_cache_size: DEFAULT_DECODE_CACHE_SIZE,
+            kernel: None,
+            force_scalar: false,
+        }
+    }
+
+    /// Set whether the code is systematic i.e. whether the data shards
+    /// remain unchanged after encoding. A non-systematic code uses the
+    /// Vandermonde matrix as it is, so every shard is a combination of
+    /// all the data shards and no data shard appears in the clear.
+    /// # Arguments
+    ///
+    /// * `systematic` - Whether the code is systematic
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).systematic(false).build();
+    /// ```
+    pub fn systematic(mut self, systematic: bool) -> ReedSolomonBuilder {
+        self.systematic = systematic;
+        self
+    }
+
+    /// Set the kind of matrix the encoding matrix is built from. A
+    /// systematic Cauchy code stacks the identity matrix on a Cauchy
+    /// matrix, which is MDS by construction so no matrix has to be
+    /// inverted to build it. A non-systematic Cauchy code uses a Cauchy
+    /// matrix with one row per shard, which limits the data shards plus
+    /// the total shards to the field size.
+    /// # Arguments
+    ///
+    /// * `matrix_kind` - Kind of the encoding matrix
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::{MatrixKind, ReedSolomonBuilder};
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).matrix_kind(MatrixKind::Cauchy).build();
+    /// ```
+    pub fn matrix_kind(mut self, matrix_kind: MatrixKind) -> ReedSolomonBuilder {
+        self.matrix_kind = matrix_kind;
+        self
+    }
+
+    /// Set the no. of inverted decode matrices kept by the code, so that
+    /// decoding the same erasure pattern again skips the inversion. The
+    /// least recently used matrix is evicted when it is full, and 0
+    /// disables it. It defaults to 16.
+    /// # Arguments
+    ///
+    /// * `size` - Max no. of cached decode matrices
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).decode_cache_size(64).build();
+    /// ```
+    pub fn decode_cache_size(mut self, size: usize) -> ReedSolomonBuilder {
+        self.decode_cache_size = size;
+        self
+    }
+
+    /// Set the kernel used to multiply the shards while encoding and
+    /// decoding, instead of the default `TableKernel`.
+    /// # Arguments
+    ///
+    /// * `kernel` - Kernel multiplying slices by an element of the field
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    /// use reed_solomon::kernel::TableKernel;
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).kernel(Arc::new(TableKernel::new())).build();
+    /// ```
+    pub fn kernel(mut self, kernel: Arc<dyn MulKernel + Send + Sync>) -> ReedSolomonBuilder {
+        self.kernel = Some(kernel);
+        self
+    }
+
+    /// Set whether to multiply through the full 64 KiB multiplication
+    /// table of the field, i.e. `FullTableKernel`, instead of the log and
+    /// exp tables of the default `TableKernel`. Enabling it replaces any
+    /// kernel set before, while disabling it only drops the full table
+    /// kernel, keeping a kernel set by `kernel`.
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to use the full multiplication table
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).full_mul_table(true).build();
+    /// ```
+    pub fn full_mul_table(mut self, enabled: bool) -> ReedSolomonBuilder {
+        if enabled {
+            self.kernel = Some(Arc::new(FullTableKernel::new()));
+        } else if self.kernel.as_ref().map(|kernel| kernel.kind()) == Some(Kernel::FullTable) {
+            self.kernel = None;
+        }
+        self
+    }
+
+    /// Set whether to force the scalar `TableKernel`, ignoring any kernel
+    /// set by `kernel` or `full_mul_table`, e.g. to rule out a faster
+    /// kernel while diagnosing a slow or wrong encode.
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to force the scalar kernel
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::Kernel;
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).force_scalar(true).build().unwrap();
+    /// assert_eq!(Kernel::Scalar, rs.active_kernel());
+    /// ```
+    pub fn force_scalar(mut self, enabled: bool) -> ReedSolomonBuilder {
+        self.force_scalar = enabled;
+        self
+    }
+
+    /// Create the Reed Solomon Erasure Coding with the configuration of
+    /// the builder.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonBuilder;
+    ///
+    /// let rs = ReedSolomonBuilder::new(4, 2).build();
+    /// ```
+    pub fn build(self) -> Result<ReedSolomon, Error> {
+        ReedSolomon::check_shard_counts(self.data_shards, self.parity_shards)?;
+
+        let gf = GaloisField::new();
+        let total_shards = self.data_shards + self.parity_shards;
+
+        let matrix = match (self.matrix_kind, self.systematic) {
+            (MatrixKind::Vandermonde, true) => {
+                ReedSolomon::build_matrix(self.data_shards, total_shards, &gf)?
+            }
+            (MatrixKind::Vandermonde, false) => {
+                Self::build_non_systematic_matrix(self.data_shards, total_shards, &gf)?
+            }
+            (MatrixKind::Cauchy, true) => {
+                Self::build_cauchy_matrix(self.data_shards, total_shards, &gf)
+            }
+            (MatrixKind::Cauchy, false) => {
+                Self::build_non_systematic_cauchy_matrix(self.data_shards, total_shards, &gf)?
+            }
+        };
+
+        let mut rs = ReedSolomon::from_matrix(
+            self.data_shards,
+            self.parity_shards,
+            matrix,
+            gf,
+            self.systematic,
+        );
+        // The code is created with the scalar kernel.
+        if let Some(kernel) = self.kernel.filter(|_| !self.force_scalar) {
+            rs.kernel = kernel;
+        }
+        if self.decode_cache_size != DEFAULT_DECODE_CACHE_SIZE {
+            rs.decode_cache = Arc::new(Mutex::new(DecodeCache::new(self.decode_cache_size)));
+        }
+
+        Ok(rs)
+    }
+
+    /// Create a Vandermonde matrix used for non-systematic encoding. The
+    /// row of the base 0 is skipped, as it would copy the first data
+    /// shard to the first shard, so the bases are 1 to total_shards.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `total_shards` - Total no. of Shards (Data + Parity)
+    /// * `gf` - Galois Field where all the arithmetic will take place
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::builder::ReedSolomonBuilder;
+    /// use crate::galois::{GaloisField, FIELD_SIZE};
+    ///
+    /// let gf = GaloisField::new();
+    /// let matrix = ReedSolomonBuilder::build_non_systematic_matrix(4, 6, &gf);
+    /// ```
+    fn build_non_systematic_matrix(
+        data_shards: usize,
+        total_shards: usize,
+        gf: &GaloisField,
+    ) -> Result<Matrix, Error> {
+        // Without the base 0 there are only 255 distinct bases.
+        if total_shards > FIELD_SIZE - 1 {
+            return Err(Error::TooManyShardsForField(total_shards, FIELD_SIZE - 1));
+        }
+
+        let mut matrix = Matrix::new(total_shards, data_shards);
+        for (r, row) in matrix.data.iter_mut().enumerate() {
+            gf.fill_vandermonde_row((r + 1) as u8, row);
+        }
+
+        Ok(matrix)
+    }
+
+    /// Create the encoding matrix of a systematic Cauchy code, i.e. the
+    /// identity matrix stacked on the Cauchy matrix whose element at row
+    /// r and column c is 1 / (x_r + y_c), with x_r = data_shards + r and
+    /// y_c = c. Since all the x and y are distinct, every square subset of
+    /// the Cauchy matrix is invertible, so the encoding matrix is MDS.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `total_shards` - Total no. of Shards (Data + Parity)
+    /// * `gf` - Galois Field where all the arithmetic will take place
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::builder::ReedSolomonBuilder;
+    /// use crate::galois::GaloisField;
+    ///
+    /// let gf = GaloisField::new();
+    /// let matrix = ReedSolomonBuilder::build_cauchy_matrix(4, 6, &gf);
+    /// ```
+    fn build_cauchy_matrix(data_shards: usize, total_shards: usize, gf: &GaloisField) -> Matrix {
+        let mut matrix = Matrix::new_identity(data_shards).into_data();
+        for x in data_shards..total_shards {
+            let row: Vec<u8> = (0..data_shards)
+                .map(|y| gf.div(1, GaloisField::add(x as u8, y as u8)))
+                .collect();
+            matrix.push(row);
+        }
+
+        Matrix::new_from_data(matrix)
+    }
+
+    /// Create the encoding matrix of a non-systematic Cauchy code, i.e.
+    /// the Cauchy matrix whose element at row r and column c is
+    /// 1 / (x_r + y_c), with x_r = r and y_c = 255 - c. The rows don't
+    /// depend on the total no. of shards, so a code with more parity
+    /// shards extends the shards of this one.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `total_shards` - Total no. of Shards (Data + Parity)
+    /// * `gf` - Galois Field where all the arithmetic will take place
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::builder::ReedSolomonBuilder;
+    /// use crate::galois::GaloisField;
+    ///
+    /// let gf = GaloisField::new();
+    /// let matrix = ReedSolomonBuilder::build_non_systematic_cauchy_matrix(4, 6, &gf);
+    /// ```
+    fn build_non_systematic_cauchy_matrix(
+        data_shards: usize,
+        total_shards: usize,
+        gf: &GaloisField,
+    ) -> Result<Matrix, Error> {
+        // The x and y of all the rows and columns have to be distinct.
+        if data_shards + total_shards > FIELD_SIZE {
+            return Err(Error::TooManyShardsForField(
+                total_shards,
+                FIELD_SIZE - data_shards,
+            ));
+        }
+
+        let mut matrix = Matrix::new(total_shards, data_shards);
+        for (x, row) in matrix.data.iter_mut().enumerate() {
+            for (c, elem) in row.iter_mut().enumerate() {
+                let y = FIELD_SIZE - 1 - c;
+                *elem = gf.div(1, GaloisField::add(x as u8, y as u8));
+            }
+        }
+
+        Ok(matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::TableKernel;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Kernel wrapping the default one and counting its calls.
+    struct CountingKernel {
+        inner: TableKernel,
+        calls: AtomicUsize,
+    }
+
+    impl MulKernel for CountingKernel {
+        fn mul_slice(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.inner.mul_slice(scalar, input, output);
+        }
+
+        fn mul_slice_add(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.inner.mul_slice_add(scalar, input, output);
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let result = ReedSolomonBuilder::new(4, 2).build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let default_rs = match ReedSolomon::new(4, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        assert!(rs.systematic);
+        assert_eq!(default_rs.matrix.data, rs.matrix.data);
+        assert!(ReedSolomonBuilder::new(0, 2).build().is_err());
+    }
+    #[test]
+    fn test_non_systematic() {
+        let result = ReedSolomonBuilder::new(2, 2).systematic(false).build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data = [vec![0, 1, 2], vec![3, 4, 5]];
+        let shards = vec![
+            data[0].clone(),
+            data[1].clone(),
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+        ];
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for shard in encoded_shard.iter() {
+            assert!(!data.contains(shard));
+        }
+
+        let broken_shards = vec![
+            vec![],
+            encoded_shard[1].clone(),
+            vec![],
+            encoded_shard[3].clone(),
+        ];
+        let decoded_shard = match rs.decode(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+
+        assert!(matches!(
+            ReedSolomonBuilder::new(200, 56).systematic(false).build(),
+            Err(Error::TooManyShardsForField(256, 255))
+        ));
+    }
+    #[test]
+    fn test_full_mul_table() {
+        let result = ReedSolomonBuilder::new(4, 2).full_mul_table(true).build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let default_rs = match ReedSolomon::new(4, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(default_rs, rs);
+        assert_eq!(Kernel::FullTable, rs.active_kernel());
+
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+        shards.resize(6, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        match default_rs.encode(shards) {
+            Ok(x) => assert_eq!(x, encoded_shard),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[1] = vec![];
+        broken_shards[4] = vec![];
+        match rs.decode(broken_shards) {
+            Ok(x) => assert_eq!(encoded_shard, x),
+            Err(e) => panic!("{}", e),
+        }
+
+        match ReedSolomonBuilder::new(4, 2)
+            .full_mul_table(true)
+            .full_mul_table(false)
+            .build()
+        {
+            Ok(x) => assert_eq!(Kernel::Scalar, x.active_kernel()),
+            Err(e) => panic!("{}", e),
+        }
+        let kernel = Arc::new(CountingKernel {
+            inner: TableKernel::new(),
+            calls: AtomicUsize::new(0),
+        });
+        match ReedSolomonBuilder::new(4, 2)
+            .kernel(kernel)
+            .full_mul_table(false)
+            .build()
+        {
+            Ok(x) => assert_eq!(Kernel::Custom, x.active_kernel()),
+            Err(e) => panic!("{}", e),
+        }
+    }
+    #[test]
+    fn test_custom_kernel() {
+        let kernel = Arc::new(CountingKernel {
+            inner: TableKernel::new(),
+            calls: AtomicUsize::new(0),
+        });
+        let result = ReedSolomonBuilder::new(4, 2).kernel(kernel.clone()).build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let default_rs = match ReedSolomon::new(4, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+        shards.resize(6, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let expected = match default_rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(expected, encoded_shard);
+        assert!(kernel.calls.load(Ordering::Relaxed) > 0);
+        assert_eq!(Kernel::Custom, rs.active_kernel());
+
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[0] = vec![];
+        broken_shards[5] = vec![];
+        let decoded_shard = match rs.decode(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(expected, decoded_shard);
+    }
+    #[test]
+    fn test_force_scalar() {
+        let kernel = Arc::new(CountingKernel {
+            inner: TableKernel::new(),
+            calls: AtomicUsize::new(0),
+        });
+        let result = ReedSolomonBuilder::new(4, 2)
+            .kernel(kernel.clone())
+            .force_scalar(true)
+            .build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(Kernel::Scalar, rs.active_kernel());
+
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+        shards.resize(6, vec![0; 3]);
+        if let Err(e) = rs.encode(shards) {
+            panic!("{}", e);
+        }
+        assert_eq!(0, kernel.calls.load(Ordering::Relaxed));
+    }
+    #[test]
+    fn test_cauchy() {
+        let gf = GaloisField::new();
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(4, 3)
+                .matrix_kind(MatrixKind::Cauchy)
+                .systematic(systematic)
+                .build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert!(matches!(rs.matrix.is_mds(&gf), Ok(true)));
+
+            let mut shards: Vec<Vec<u8>> = (0..4).map(|i| vec![i, i * 3, 250 - i]).collect();
+            shards.resize(7, vec![0; 3]);
+            let encoded = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut broken = encoded.clone();
+            broken[0] = vec![];
+            broken[2] = vec![];
+            broken[5] = vec![];
+            match rs.decode(broken) {
+                Ok(x) => assert_eq!(encoded, x),
+                Err(e) => panic!("{}", e),
+            }
+        }
+
+        // The parity of 1 + 1 is 1 / (1 + 0).
+        let result = ReedSolomonBuilder::new(1, 1)
+            .matrix_kind(MatrixKind::Cauchy)
+            .build();
+        match result {
+            Ok(x) => assert_eq!(vec![vec![1], vec![1]], x.matrix.data),
+            Err(e) => panic!("{}", e),
+        }
+        assert!(ReedSolomonBuilder::new(128, 128)
+            .matrix_kind(MatrixKind::Cauchy)
+            .build()
+            .is_ok());
+        assert!(ReedSolomonBuilder::new(128, 1)
+            .matrix_kind(MatrixKind::Cauchy)
+            .systematic(false)
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::galois::FIELD_SIZE;
+use crate::matrix::Matrix;
+
+/// Default no. of decode matrices kept by a code.
+pub(crate) const DEFAULT_DECODE_CACHE_SIZE: usize = 16;
+
+/// Bitmask of the shards a decode matrix was inverted from, one bit per
+/// shard.
+pub(crate) type ShardMask = [u64; FIELD_SIZE / 64];
+
+/// A bounded cache of inverted decode matrices keyed by the shards they
+/// were inverted from, so that decoding the same erasure pattern again
+/// skips the Gaussian elimination. When it is full the least recently
+/// used matrix is evicted.
+pub(crate) struct DecodeCache {
+    capacity: usize,
+    // The most recently used entry is the last one.
+    entries: Vec<(ShardMask, Matrix)>,
+}
+
+impl DecodeCache {
+    /// Create a new empty DecodeCache. A capacity of 0 disables it.
+    /// # Arguments
+    ///
+    /// * `capacity` - Max no. of matrices kept
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::cache::DecodeCache;
+    ///
+    /// let cache = DecodeCache::new(16);
+    /// ```
+    pub(crate) fn new(capacity: usize) -> DecodeCache {
+        DecodeCache {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the bitmask of the given shard indices.
+    /// # Arguments
+    ///
+    /// * `rows` - Indices of the shards, each less than the field size
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::cache::DecodeCache;
+    ///
+    /// let key = DecodeCache::key(&[0, 2, 3]);
+    /// ```
+    pub(crate) fn key(rows: &[usize]) -> ShardMask {
+        let mut key: ShardMask = [0; FIELD_SIZE / 64];
+        for &row in rows.iter() {
+            key[row / 64] |= 1 << (row % 64);
+        }
+
+        key
+    }
+
+    /// Returns a copy of the matrix cached for the given key, and marks
+    /// it as the most recently used one.
+    /// # Arguments
+    ///
+    /// * `key` - Bitmask of the shards the matrix was inverted from
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::cache::DecodeCache;
+    ///
+    /// let mut cache = DecodeCache::new(16);
+    /// let matrix = cache.get(&DecodeCache::key(&[0, 2, 3]));
+    /// ```
+    pub(crate) fn get(&mut self, key: &ShardMask) -> Option<Matrix> {
+        let pos = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(pos);
+        let matrix = entry.1.clone();
+        self.entries.push(entry);
+
+        Some(matrix)
+    }
+
+    /// Caches the matrix for the given key, evicting the least recently
+    /// used matrix if the cache is full.
+    /// # Arguments
+    ///
+    /// * `key` - Bitmask of the shards the matrix was inverted from
+    /// * `matrix` - Inverted decode matrix
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::cache::DecodeCache;
+    /// use crate::matrix::Matrix;
+    ///
+    /// let mut cache = DecodeCache::new(16);
+    /// cache.insert(DecodeCache::key(&[0, 1]), Matrix::new_identity(2));
+    /// ```
+    pub(crate) fn insert(&mut self, key: ShardMask, matrix: Matrix) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|(k, _)| *k != key);
+        if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, matrix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_cache() {
+        let mut cache = DecodeCache::new(2);
+        let keys = [
+            DecodeCache::key(&[0, 1]),
+            DecodeCache::key(&[0, 200]),
+            DecodeCache::key(&[1, 2]),
+        ];
+        assert_eq!(1 << 8, keys[1][3]);
+
+        cache.insert(keys[0], Matrix::new_identity(1));
+        cache.insert(keys[1], Matrix::new_identity(2));
+        // Using the 1st matrix makes the 2nd one the least recently used.
+        assert_eq!(Some(Matrix::new_identity(1)), cache.get(&keys[0]));
+        cache.insert(keys[2], Matrix::new_identity(3));
+        assert_eq!(None, cache.get(&keys[1]));
+        assert_eq!(Some(Matrix::new_identity(1)), cache.get(&keys[0]));
+        assert_eq!(Some(Matrix::new_identity(3)), cache.get(&keys[2]));
+
+        let mut disabled = DecodeCache::new(0);
+        disabled.insert(keys[0], Matrix::new_identity(1));
+        assert_eq!(None, disabled.get(&keys[0]));
+    }
+}
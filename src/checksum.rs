@@ -0,0 +1,98 @@
+/// Offset basis of the 64 bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+
+/// Prime of the 64 bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Computes the 64 bit FNV-1a hash of the given bytes. It is fast but
+/// not cryptographic.
+/// # Arguments
+///
+/// * `bytes` - Bytes to be hashed
+///
+/// # Example
+/// ```ignore
+/// use crate::checksum::fnv1a64;
+///
+/// let hash = fnv1a64(&[0, 1, 2]);
+/// ```
+pub(crate) fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for &byte in bytes.iter() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
+
+/// Reflected polynomial of the CRC-64/XZ checksum (ECMA-182).
+const CRC64_POLYNOMIAL: u64 = 0xC96C_5795_D787_0F42;
+
+/// Lookup table of the CRC-64/XZ checksum, one entry per byte value.
+const CRC64_TABLE: [u64; 256] = gen_crc64_table();
+
+/// Generates the lookup table of the CRC-64/XZ checksum.
+const fn gen_crc64_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            if crc & 1 == 1 {
+                crc = (crc >> 1) ^ CRC64_POLYNOMIAL;
+            } else {
+                crc >>= 1;
+            }
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// Computes the CRC-64/XZ checksum of the concatenation of the given
+/// parts, without copying them together. Unlike FNV-1a it is guaranteed to
+/// detect any burst of corrupted bits up to 64 bits long.
+/// # Arguments
+///
+/// * `parts` - Bytes to be checksummed, one after the other
+///
+/// # Example
+/// ```ignore
+/// use crate::checksum::crc64;
+///
+/// let crc = crc64(&[&[0, 1], &[2]]);
+/// ```
+pub(crate) fn crc64(parts: &[&[u8]]) -> u64 {
+    let mut crc = !0u64;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        crc = CRC64_TABLE[((crc ^ byte as u64) & 0xFF) as usize] ^ (crc >> 8);
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a64() {
+        // Reference values of the FNV-1a 64 bit hash.
+        assert_eq!(0xCBF2_9CE4_8422_2325, fnv1a64(b""));
+        assert_eq!(0xAF63_DC4C_8601_EC8C, fnv1a64(b"a"));
+        assert_eq!(0x8594_4171_F739_67E8, fnv1a64(b"foobar"));
+    }
+
+    #[test]
+    fn test_crc64() {
+        // Reference values of the CRC-64/XZ checksum.
+        assert_eq!(0, crc64(&[b""]));
+        assert_eq!(0x995D_C9BB_DF19_39FA, crc64(&[b"123456789"]));
+        assert_eq!(0x995D_C9BB_DF19_39FA, crc64(&[b"1234", b"", b"56789"]));
+    }
+}
@@ -0,0 +1,535 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The errors of the reed-solomon-erasure crate, with the same variants, so
+/// that call sites matching on them keep compiling. The errors of this crate
+/// are converted with `From`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    TooFewShards,
+    TooManyShards,
+    TooFewDataShards,
+    TooManyDataShards,
+    TooFewParityShards,
+    TooManyParityShards,
+    TooFewBufferShards,
+    TooManyBufferShards,
+    IncorrectShardSize,
+    TooFewShardsPresent,
+    EmptyShard,
+    InvalidShardFlags,
+    InvalidIndex,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooFewShards => write!(
+                f,
+                "The no. of given shards is smaller than the total no. of shards"
+            ),
+            Error::TooManyShards => write!(
+                f,
+                "The no. of given shards is larger than the total no. of shards"
+            ),
+            Error::TooFewDataShards => write!(f, "The no. of given data shards is too small"),
+            Error::TooManyDataShards => write!(f, "The no. of given data shards is too large"),
+            Error::TooFewParityShards => write!(f, "The no. of given parity shards is too small"),
+            Error::TooManyParityShards => write!(f, "The no. of given parity shards is too large"),
+            Error::TooFewBufferShards => write!(f, "The no. of given buffer shards is too small"),
+            Error::TooManyBufferShards => write!(f, "The no. of given buffer shards is too large"),
+            Error::IncorrectShardSize => write!(f, "Length of the given shards are different"),
+            Error::TooFewShardsPresent => write!(f, "Too few shards are present to reconstruct"),
+            Error::EmptyShard => write!(f, "There is a empty shard"),
+            Error::InvalidShardFlags => {
+                write!(f, "The no. of shard flags doesn't match the no. of shards")
+            }
+            Error::InvalidIndex => write!(f, "Shard index is out of bounds"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<crate::error::Error> for Error {
+    /// Maps an error of this crate to the closest error of the
+    /// reed-solomon-erasure crate. The errors without a counterpart, which
+    /// all come from a failed decode, become `TooFewShardsPresent`.
+    fn from(err: crate::error::Error) -> Self {
+        use crate::error::Error as E;
+        match err {
+            E::ZeroDataShards => Error::TooFewDataShards,
+            E::ZeroParityShards => Error::TooFewParityShards,
+            E::ShardsOverflow | E::TooManyShardsForField(..) | E::TooManyShards => {
+                Error::TooManyShards
+            }
+            E::WrongNoOfShards => Error::TooFewShards,
+            E::EmptyShards => Error::EmptyShard,
+            E::InconsistentShards => Error::IncorrectShardSize,
+            E::TooFewShards => Error::TooFewShardsPresent,
+            E::InvalidShardIndex(_) => Error::InvalidIndex,
+            _ => Error::TooFewShardsPresent,
+        }
+    }
+}
+
+/// A shard given to `ReedSolomon::reconstruct`, like the `ReconstructShard`
+/// trait of the reed-solomon-erasure crate. It is implemented for
+/// `Option<T>`, where a missing shard is `None`, and for `(T, bool)`, where
+/// a missing shard is a buffer of the shard length flagged `false`.
+pub trait ReconstructShard {
+    /// Returns the shard, or `None` if it is missing.
+    fn get(&self) -> Option<&[u8]>;
+
+    /// Returns whether a recovered shard of the given length can be stored.
+    fn can_store(&self, len: usize) -> bool;
+
+    /// Stores a recovered shard in place of the missing one.
+    fn store(&mut self, shard: Vec<u8>);
+}
+
+impl<T: AsRef<[u8]> + From<Vec<u8>>> ReconstructShard for Option<T> {
+    fn get(&self) -> Option<&[u8]> {
+        self.as_ref().map(|x| x.as_ref())
+    }
+
+    fn can_store(&self, _len: usize) -> bool {
+        true
+    }
+
+    fn store(&mut self, shard: Vec<u8>) {
+        *self = Some(T::from(shard));
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> ReconstructShard for (T, bool) {
+    fn get(&self) -> Option<&[u8]> {
+        if self.1 {
+            Some(self.0.as_ref())
+        } else {
+            None
+        }
+    }
+
+    fn can_store(&self, len: usize) -> bool {
+        self.0.as_ref().len() == len
+    }
+
+    fn store(&mut self, shard: Vec<u8>) {
+        self.0.as_mut().copy_from_slice(&shard);
+        self.1 = true;
+    }
+}
+
+/// Checks a no. of shards against the expected one, failing with the given
+/// errors when there are too few or too many.
+fn check_count(
+    count: usize,
+    expected: usize,
+    too_few: Error,
+    too_many: Error,
+) -> Result<(), Error> {
+    match count.cmp(&expected) {
+        Ordering::Less => Err(too_few),
+        Ordering::Greater => Err(too_many),
+        Ordering::Equal => Ok(()),
+    }
+}
+
+/// A Struct with the same shape of API as `galois_8::ReedSolomon` of the
+/// reed-solomon-erasure crate, so that its call sites can switch to this
+/// crate by changing the import. The shards are the same, and the errors are
+/// the ones of `compat::Error`.
+///
+/// Only the GF(2^8) codec is mirrored: the `Field` generic parameter,
+/// `galois_16`, `ShardByShard` and `SBSError` of the reed-solomon-erasure
+/// crate have no counterpart here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReedSolomon {
+    rs: crate::ReedSolomon,
+}
+
+impl ReedSolomon {
+    /// Create a new Reed Solomon Erasure Coding.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// ```
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<ReedSolomon, Error> {
+        Ok(ReedSolomon {
+            rs: crate::ReedSolomon::new(data_shards, parity_shards)?,
+        })
+    }
+
+    /// Returns the no. of data shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(4, rs.data_shard_count());
+    /// ```
+    pub fn data_shard_count(&self) -> usize {
+        self.rs.data_shard_count()
+    }
+
+    /// Returns the no. of parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(2, rs.parity_shard_count());
+    /// ```
+    pub fn parity_shard_count(&self) -> usize {
+        self.rs.parity_shard_count()
+    }
+
+    /// Returns the total no. of shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(6, rs.total_shard_count());
+    /// ```
+    pub fn total_shard_count(&self) -> usize {
+        self.rs.total_shard_count()
+    }
+
+    /// Encodes the parity shards in place from the data shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+    /// rs.encode(&mut shards).unwrap();
+    /// ```
+    pub fn encode<T, U>(&self, mut shards: T) -> Result<(), Error>
+    where
+        T: AsRef<[U]> + AsMut<[U]>,
+        U: AsRef<[u8]> + AsMut<[u8]>,
+    {
+        let shards = shards.as_mut();
+        check_count(
+            shards.len(),
+            self.total_shard_count(),
+            Error::TooFewShards,
+            Error::TooManyShards,
+        )?;
+        Ok(self.rs.encode_in_place(shards)?)
+    }
+
+    /// Encodes the parity shards from the data shards given apart.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards
+    /// * `parity` - Parity shards (to be overwritten)
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    /// let mut parity = vec![vec![0; 3]; 2];
+    /// rs.encode_sep(&data, &mut parity).unwrap();
+    /// ```
+    pub fn encode_sep<T: AsRef<[u8]>, U: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        data: &[T],
+        parity: &mut [U],
+    ) -> Result<(), Error> {
+        check_count(
+            data.len(),
+            self.data_shard_count(),
+            Error::TooFewDataShards,
+            Error::TooManyDataShards,
+        )?;
+        check_count(
+            parity.len(),
+            self.parity_shard_count(),
+            Error::TooFewParityShards,
+            Error::TooManyParityShards,
+        )?;
+        Ok(self.rs.encode_into(data, parity)?)
+    }
+
+    /// Check whether the parity shards match the data shards.
+    /// # Arguments
+    ///
+    /// * `slices` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 11, 12], vec![5, 14, 11]];
+    /// assert!(rs.verify(&shards).unwrap());
+    /// ```
+    pub fn verify<T: AsRef<[u8]>>(&self, slices: &[T]) -> Result<bool, Error> {
+        check_count(
+            slices.len(),
+            self.total_shard_count(),
+            Error::TooFewShards,
+            Error::TooManyShards,
+        )?;
+        Ok(self.rs.verify(slices)?)
+    }
+
+    /// Recover every missing data or parity shard in place.
+    /// # Arguments
+    ///
+    /// * `slices` - All shards including data and parity shards. Missing shards are `None`, or flagged `false`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![Some(vec![0, 1, 2]), None, None, Some(vec![5, 14, 11])];
+    /// rs.reconstruct(&mut shards).unwrap();
+    ///
+    /// let mut flagged = vec![(vec![0, 1, 2], true), (vec![0; 3], false), (vec![0; 3], false), (vec![5, 14, 11], true)];
+    /// rs.reconstruct(&mut flagged).unwrap();
+    /// ```
+    pub fn reconstruct<T: ReconstructShard>(&self, slices: &mut [T]) -> Result<(), Error> {
+        self.reconstruct_with(slices, false)
+    }
+
+    /// Recover only the missing data shards in place.
+    /// # Arguments
+    ///
+    /// * `slices` - All shards including data and parity shards. Missing shards are `None`, or flagged `false`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![Some(vec![0, 1, 2]), None, None, Some(vec![5, 14, 11])];
+    /// rs.reconstruct_data(&mut shards).unwrap();
+    /// ```
+    pub fn reconstruct_data<T: ReconstructShard>(&self, slices: &mut [T]) -> Result<(), Error> {
+        self.reconstruct_with(slices, true)
+    }
+
+    /// Recover the missing shards, or only the missing data shards, and
+    /// store them. Nothing is stored unless every missing shard fits, so on
+    /// error the given shards are untouched.
+    /// # Arguments
+    ///
+    /// * `slices` - All shards including data and parity shards. Missing shards are `None`, or flagged `false`.
+    /// * `data_only` - Whether to recover only the data shards
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::compat::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![Some(vec![0, 1, 2]), None, None, Some(vec![5, 14, 11])];
+    /// rs.reconstruct_with(&mut shards, false).unwrap();
+    /// ```
+    fn reconstruct_with<T: ReconstructShard>(
+        &self,
+        slices: &mut [T],
+        data_only: bool,
+    ) -> Result<(), Error> {
+        check_count(
+            slices.len(),
+            self.total_shard_count(),
+            Error::TooFewShards,
+            Error::TooManyShards,
+        )?;
+
+        let recovered: Vec<(usize, Vec<u8>)> = {
+            let mut shards: Vec<Option<Cow<'_, [u8]>>> =
+                slices.iter().map(|x| x.get().map(Cow::Borrowed)).collect();
+            let missing: Vec<usize> = (0..shards.len()).filter(|&i| shards[i].is_none()).collect();
+            if data_only {
+                self.rs.reconstruct_data(&mut shards)?;
+            } else {
+                self.rs.reconstruct(&mut shards)?;
+            }
+            missing
+                .into_iter()
+                .filter_map(|i| shards[i].take().map(|x| (i, x.into_owned())))
+                .collect()
+        };
+
+        if recovered
+            .iter()
+            .any(|(i, shard)| !slices[*i].can_store(shard.len()))
+        {
+            return Err(Error::IncorrectShardSize);
+        }
+        for (i, shard) in recovered {
+            slices[i].store(shard);
+        }
+        Ok(())
+    }
+}
+
+impl From<crate::ReedSolomon> for ReedSolomon {
+    fn from(rs: crate::ReedSolomon) -> Self {
+        ReedSolomon { rs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compat_call_sites() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(5, rs.total_shard_count());
+
+        let mut shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![0; 3],
+            vec![0; 3],
+        ];
+        if let Err(e) = rs.encode(&mut shards) {
+            panic!("{}", e);
+        }
+        match rs.verify(&shards) {
+            Ok(x) => assert!(x),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut parity = vec![vec![0; 3]; 2];
+        if let Err(e) = rs.encode_sep(&shards[..3], &mut parity) {
+            panic!("{}", e);
+        }
+        assert_eq!(shards[3..], parity[..]);
+
+        let mut broken: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+        broken[0] = None;
+        broken[4] = None;
+        if let Err(e) = rs.reconstruct(&mut broken) {
+            panic!("{}", e);
+        }
+        assert_eq!(shards, broken.into_iter().flatten().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_compat_errors() {
+        assert!(matches!(
+            ReedSolomon::new(0, 2),
+            Err(Error::TooFewDataShards)
+        ));
+        assert!(matches!(
+            ReedSolomon::new(2, 0),
+            Err(Error::TooFewParityShards)
+        ));
+        assert!(matches!(
+            ReedSolomon::new(200, 100),
+            Err(Error::TooManyShards)
+        ));
+
+        let rs = match ReedSolomon::new(2, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3]];
+        assert!(matches!(rs.encode(&mut shards), Err(Error::TooFewShards)));
+        shards.push(vec![0; 3]);
+        shards.push(vec![0; 3]);
+        assert!(matches!(rs.encode(&mut shards), Err(Error::TooManyShards)));
+        shards.pop();
+        shards[1] = vec![3, 4];
+        assert!(matches!(
+            rs.encode(&mut shards),
+            Err(Error::IncorrectShardSize)
+        ));
+        assert!(matches!(
+            rs.verify(&vec![vec![0u8; 0]; 4]),
+            Err(Error::EmptyShard)
+        ));
+
+        let mut parity = vec![vec![0; 3]; 3];
+        assert!(matches!(
+            rs.encode_sep(&[vec![0, 1, 2]], &mut parity),
+            Err(Error::TooFewDataShards)
+        ));
+        assert!(matches!(
+            rs.encode_sep(&[vec![0, 1, 2], vec![3, 4, 5]], &mut parity),
+            Err(Error::TooManyParityShards)
+        ));
+
+        let mut broken = vec![Some(vec![0, 1, 2]), None, None, None];
+        assert!(matches!(
+            rs.reconstruct(&mut broken),
+            Err(Error::TooFewShardsPresent)
+        ));
+    }
+
+    #[test]
+    fn test_compat_flagged_shards() {
+        let rs = match ReedSolomon::new(2, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 11, 12],
+            vec![5, 14, 11],
+        ];
+
+        let mut flagged: Vec<(Vec<u8>, bool)> = shards.iter().cloned().map(|x| (x, true)).collect();
+        flagged[1] = (vec![0; 3], false);
+        flagged[2] = (vec![0; 3], false);
+        if let Err(e) = rs.reconstruct(&mut flagged) {
+            panic!("{}", e);
+        }
+        assert!(flagged.iter().all(|(_, present)| *present));
+        assert_eq!(
+            shards,
+            flagged.into_iter().map(|(x, _)| x).collect::<Vec<_>>()
+        );
+
+        let mut buffers = shards.clone();
+        let mut slices: Vec<(&mut [u8], bool)> = buffers
+            .iter_mut()
+            .map(|x| (x.as_mut_slice(), true))
+            .collect();
+        slices[0].1 = false;
+        slices[0].0.fill(0);
+        if let Err(e) = rs.reconstruct_data(&mut slices) {
+            panic!("{}", e);
+        }
+        assert_eq!(shards, buffers);
+
+        let mut short: Vec<(Vec<u8>, bool)> = shards.iter().cloned().map(|x| (x, true)).collect();
+        short[3] = (vec![0; 2], false);
+        assert!(matches!(
+            rs.reconstruct(&mut short),
+            Err(Error::IncorrectShardSize)
+        ));
+        assert!(!short[3].1);
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::io;
 
 #[derive(Debug)]
 pub enum Error {
@@ -14,6 +15,30 @@ pub enum Error {
     InconsistentShards,
     TooFewShards,
     TooManyShards,
+    RowOutOfBounds(usize, usize),
+    NoCodeForImportance(u8),
+    InvalidHeader,
+    InvalidShardIndex(usize),
+    TooManyShardsForField(usize, usize),
+    ZeroAlignment,
+    NotMds,
+    Cancelled,
+    SingularAtRow(usize),
+    EmptyMatrix,
+    InconsistentRowLengths,
+    InvalidLayout,
+    Io(io::Error),
+    Internal(String),
+    ConfigTooExpensive(u64, u64),
+    NotSystematic,
+    OddShardLength(usize),
+    DataLenOutOfBounds(usize, usize),
+    UnsupportedMatrix,
+    TooManyErrors,
+    ZeroStripeSize,
+    DuplicateShardIndex(usize),
+    ParityMismatch(Vec<usize>),
+    InvalidStripeIndex(usize),
 }
 
 impl fmt::Display for Error {
@@ -33,12 +58,70 @@ impl fmt::Display for Error {
             Error::SingularMatrix =>  write!(f, "The given matrix is singular"),
             Error::ZeroDataShards =>  write!(f, "Data Shards can't be zero"),
             Error::ZeroParityShards =>  write!(f, "Parity Shards can't be zero"),
-            Error::ShardsOverflow =>  write!(f, "More than 256 shards are nt allowed"),
+            Error::ShardsOverflow =>  write!(f, "The total no. of shards overflows"),
             Error::WrongNoOfShards =>  write!(f, "Wrong no. of shards"),
             Error::EmptyShards =>  write!(f, "There is a empty shards"),
             Error::InconsistentShards =>  write!(f, "Length of the given shards are different"),
             Error::TooFewShards =>  write!(f, "Too few no. of shards"),
             Error::TooManyShards =>  write!(f, "Too many no. of shards"),
+            Error::RowOutOfBounds(row, rows) => write!(
+                f,
+                "Row index is out of bounds. row: {}, row count: {}",
+                *row, *rows
+            ),
+            Error::NoCodeForImportance(importance) => write!(
+                f,
+                "There is no code for the given importance. importance: {}",
+                *importance
+            ),
+            Error::InvalidHeader => write!(f, "The header of the shards is invalid"),
+            Error::InvalidShardIndex(index) => write!(f, "Shard index is out of bounds. index: {}", *index),
+            Error::TooManyShardsForField(total_shards, max_shards) => write!(
+                f,
+                "Too many total shards for this field. total: {}, maximum: {}",
+                *total_shards, *max_shards
+            ),
+            Error::ZeroAlignment => write!(f, "Alignment can't be zero"),
+            Error::NotMds => write!(f, "Some data shard count shards of the code can't decode"),
+            Error::Cancelled => write!(f, "The operation was cancelled"),
+            Error::SingularAtRow(row) => write!(f, "The given matrix is singular. Elimination stalled at row: {}", *row),
+            Error::EmptyMatrix => write!(f, "The matrix must have at least one row and one column"),
+            Error::InconsistentRowLengths => write!(f, "Length of the given rows are different"),
+            Error::InvalidLayout => write!(f, "Every data and parity shard must appear exactly once in the layout"),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Internal(message) => write!(f, "Internal error: {}", message),
+            Error::ConfigTooExpensive(ops, max_ops) => write!(
+                f,
+                "The configuration is too expensive. estimated operations: {}, budget: {}",
+                *ops, *max_ops
+            ),
+            Error::NotSystematic => write!(f, "The operation needs a systematic code"),
+            Error::OddShardLength(len) => write!(
+                f,
+                "Length of the shards must be a multiple of 2 for 16 bit symbols. length: {}",
+                *len
+            ),
+            Error::DataLenOutOfBounds(len, capacity) => write!(
+                f,
+                "Length of the data is more than the data shards hold. length: {}, capacity: {}",
+                *len, *capacity
+            ),
+            Error::UnsupportedMatrix => write!(f, "The operation needs a Vandermonde based encoding matrix"),
+            Error::TooManyErrors => write!(f, "Too many shards are corrupted to be corrected"),
+            Error::ZeroStripeSize => write!(f, "Stripe size can't be zero"),
+            Error::DuplicateShardIndex(index) => write!(f, "Shard index is given more than once. index: {}", *index),
+            Error::ParityMismatch(suspects) => write!(
+                f,
+                "The decoded shards don't match the parity. suspects: {:?}",
+                suspects
+            ),
+            Error::InvalidStripeIndex(index) => write!(f, "Stripe index is out of bounds. index: {}", *index),
         }
     }
 }
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,255 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+
+/// A Struct to represent a Reed Solomon Erasure Coding whose no. of data
+/// shards `K` and parity shards `M` are known at compile time. The shards
+/// are fixed-size arrays, so that encoding and verifying don't allocate
+/// and the optimizer sees the loop bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReedSolomonConst<const K: usize, const M: usize> {
+    rs: ReedSolomon,
+    parity: [[u8; K]; M],
+}
+
+impl<const K: usize, const M: usize> ReedSolomonConst<K, M> {
+    /// No. of data shards.
+    pub const DATA_SHARDS: usize = K;
+
+    /// No. of parity shards.
+    pub const PARITY_SHARDS: usize = M;
+
+    /// Total no. of shards, i.e. data shards plus parity shards.
+    pub const TOTAL_SHARDS: usize = K + M;
+
+    /// Create a new ReedSolomonConst with the same encoding matrix as
+    /// `ReedSolomon::new(K, M)`, so that shards of both are interchangeable.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonConst;
+    ///
+    /// let rs = ReedSolomonConst::<4, 2>::new();
+    /// ```
+    pub fn new() -> Result<ReedSolomonConst<K, M>, Error> {
+        let rs = ReedSolomon::new(K, M)?;
+        let mut parity = [[0; K]; M];
+        for (row, coeffs) in parity.iter_mut().zip(rs.parity.data.iter()) {
+            row.copy_from_slice(coeffs);
+        }
+
+        Ok(ReedSolomonConst { rs, parity })
+    }
+
+    /// Returns the equivalent code with shard counts known at runtime, for
+    /// the operations only available on `ReedSolomon`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonConst;
+    ///
+    /// let rs = ReedSolomonConst::<4, 2>::new().unwrap();
+    /// assert_eq!(6, rs.code().total_shard_count());
+    /// ```
+    pub fn code(&self) -> &ReedSolomon {
+        &self.rs
+    }
+
+    /// Encodes checksum shards for the given data shards.
+    /// Returns the parity shards, which are built on the stack.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonConst;
+    ///
+    /// let rs = ReedSolomonConst::<2, 2>::new().unwrap();
+    /// let parity = rs.encode(&[[0, 1, 2], [3, 4, 5]]);
+    /// assert_eq!([[6, 11, 12], [5, 14, 11]], parity);
+    /// ```
+    pub fn encode<const N: usize>(&self, data: &[[u8; N]; K]) -> [[u8; N]; M] {
+        let mut parity = [[0; N]; M];
+        for (row, output) in self.parity.iter().zip(parity.iter_mut()) {
+            self.rs.combine_row(row, data, output);
+        }
+
+        parity
+    }
+
+    /// Check whether the parity shards match the data shards. The parity
+    /// is computed into a buffer on the stack one shard at a time.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards
+    /// * `parity` - Parity shards
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonConst;
+    ///
+    /// let rs = ReedSolomonConst::<2, 2>::new().unwrap();
+    /// assert!(rs.verify(&[[0, 1, 2], [3, 4, 5]], &[[6, 11, 12], [5, 14, 11]]));
+    /// ```
+    pub fn verify<const N: usize>(&self, data: &[[u8; N]; K], parity: &[[u8; N]; M]) -> bool {
+        let mut buffer = [0; N];
+        self.parity.iter().zip(parity.iter()).all(|(row, shard)| {
+            self.rs.combine_row(row, data, &mut buffer);
+            buffer == *shard
+        })
+    }
+
+    /// Takes shards as input, with `None` for every missing shard, and
+    /// recover every missing data or parity shard in place.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards. Missing shards are `None`.
+    /// * `parity` - Parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomonConst;
+    ///
+    /// let rs = ReedSolomonConst::<2, 2>::new().unwrap();
+    /// let mut data = [Some([0, 1, 2]), None];
+    /// let mut parity = [None, Some([5, 14, 11])];
+    /// rs.reconstruct(&mut data, &mut parity).unwrap();
+    /// assert_eq!(Some([3, 4, 5]), data[1]);
+    /// ```
+    pub fn reconstruct<const N: usize>(
+        &self,
+        data: &mut [Option<[u8; N]>; K],
+        parity: &mut [Option<[u8; N]>; M],
+    ) -> Result<(), Error> {
+        let shard = |i: usize| {
+            if i < K {
+                data[i].as_ref()
+            } else {
+                parity[i - K].as_ref()
+            }
+        };
+        let missing: Vec<usize> = (0..K + M).filter(|&i| shard(i).is_none()).collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        // Any data shard count present shards can decode, the same way
+        // as `ReedSolomon::reconstruct` picks them.
+        let mut sub_matrix_rows = [0; K];
+        let mut inputs: [&[u8]; K] = [&[]; K];
+        let mut present = (0..K + M).filter_map(|i| shard(i).map(|x| (i, x)));
+        for (row, input) in sub_matrix_rows.iter_mut().zip(inputs.iter_mut()) {
+            match present.next() {
+                Some((i, x)) => {
+                    *row = i;
+                    *input = x;
+                }
+                None => return Err(Error::TooFewShards),
+            }
+        }
+
+        let data_decode_matrix = self.rs.decode_matrix(&sub_matrix_rows)?;
+        let matrix_rows = self
+            .rs
+            .matrix
+            .submatrix_by_rows(&missing)?
+            .mul(data_decode_matrix, &self.rs.gf)?;
+        let outputs: Vec<[u8; N]> = matrix_rows
+            .data
+            .iter()
+            .map(|row| {
+                let mut output = [0; N];
+                self.rs.combine_row(row, &inputs, &mut output);
+                output
+            })
+            .collect();
+
+        // Filling the missing shards.
+        for (&i, output) in missing.iter().zip(outputs) {
+            if i < K {
+                data[i] = Some(output);
+            } else {
+                parity[i - K] = Some(output);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const_matches_dynamic() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let rs_const = match ReedSolomonConst::<3, 2>::new() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(5, ReedSolomonConst::<3, 2>::TOTAL_SHARDS);
+        assert_eq!(&rs, rs_const.code());
+
+        let data = [[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]];
+        let mut shards: Vec<Vec<u8>> = data.iter().map(|x| x.to_vec()).collect();
+        shards.resize(5, vec![0; 4]);
+        let encoded = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let parity = rs_const.encode(&data);
+        assert_eq!(encoded[3], parity[0]);
+        assert_eq!(encoded[4], parity[1]);
+        assert!(rs_const.verify(&data, &parity));
+
+        let mut corrupted = parity;
+        corrupted[1][2] ^= 1;
+        assert!(!rs_const.verify(&data, &corrupted));
+    }
+
+    #[test]
+    fn test_const_reconstruct() {
+        let rs = match ReedSolomonConst::<3, 2>::new() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data = [[0, 1, 2], [3, 4, 5], [6, 7, 8]];
+        let parity = rs.encode(&data);
+
+        let mut broken_data = [Some(data[0]), None, None];
+        let mut broken_parity = [Some(parity[0]), Some(parity[1])];
+        match rs.reconstruct(&mut broken_data, &mut broken_parity) {
+            Ok(_) => assert_eq!([Some(data[0]), Some(data[1]), Some(data[2])], broken_data),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut broken_data = [Some(data[0]), Some(data[1]), None];
+        let mut broken_parity = [None, Some(parity[1])];
+        match rs.reconstruct(&mut broken_data, &mut broken_parity) {
+            Ok(_) => {
+                assert_eq!(Some(data[2]), broken_data[2]);
+                assert_eq!(Some(parity[0]), broken_parity[0]);
+            }
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut broken_data = [Some(data[0]), None, None];
+        let mut broken_parity = [None, Some(parity[1])];
+        match rs.reconstruct(&mut broken_data, &mut broken_parity) {
+            Err(Error::TooFewShards) => {}
+            _ => panic!("Too few shards must be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_const_invalid_counts() {
+        match ReedSolomonConst::<0, 2>::new() {
+            Err(Error::ZeroDataShards) => {}
+            _ => panic!("Zero data shards must be rejected"),
+        }
+    }
+}
@@ -1,5 +1,10 @@
+use std::fmt;
+use std::hint::black_box;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
 /// This size of the field i.e. 2^8.
-const FIELD_SIZE: usize = 256;
+pub(crate) const FIELD_SIZE: usize = 256;
 
 /// Size of the exponent table. The highest log value is FIELD_SIZE - 2
 /// so we decreased 2 from FIELD_SIZE. The table is repeated a 2nd time
@@ -9,47 +14,107 @@ const EXP_TABLE_SIZE: usize = FIELD_SIZE * 2 - 2;
 /// The irreducible polynomial which is used to generate the log and
 /// exp table. The possibilities are 29, 43, 45, 77, 95, 99, 101, 105,
 /// 113, 135, 141, 169, 195, 207, 231, and 245.
-const IRREDUCIBLE_POLYNOMIAL: usize = 29;
+pub(crate) const IRREDUCIBLE_POLYNOMIAL: usize = 29;
+
+/// The implementation used to multiply elements of the field. The
+/// `Ssse3`, `Avx2`, `Neon` and `Portable` variants are reserved for future
+/// SIMD backends and are never reported yet, and more may be added.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Kernel {
+    /// Multiplication through the log and exp tables, one byte at a time.
+    Scalar,
+    /// Multiplication through SSSE3 shuffles on x86.
+    Ssse3,
+    /// Multiplication through AVX2 shuffles on x86.
+    Avx2,
+    /// Multiplication through NEON table lookups on ARM.
+    Neon,
+    /// Multiplication through portable SIMD.
+    Portable,
+    /// Multiplication through the full 64 KiB multiplication table, one
+    /// byte at a time.
+    FullTable,
+    /// Multiplication through a kernel passed to
+    /// `ReedSolomonBuilder::kernel`, whose implementation is unknown.
+    Custom,
+}
 
-/// A Struct to represent the Galois Field
-#[derive(Copy, Clone)]
-pub(crate) struct GaloisField {
+/// The log and exp tables of GF(2^8).
+#[derive(PartialEq, Eq)]
+struct Tables {
     log_table: [u8; FIELD_SIZE],
     exp_table: [u8; EXP_TABLE_SIZE],
 }
 
-/// Generate the log table given an irreducible polynomial which maps
-/// the elements of the Galois field to their discrete logarithm. Since there is
-/// no log for 0 so the entry in 0th index can be ignored.
+/// The tables are generated by the first GaloisField and shared by all
+/// of them, so creating a code doesn't regenerate them.
+static TABLES: OnceLock<Tables> = OnceLock::new();
+
+/// A Struct to represent the Galois Field. It only holds a reference to
+/// the shared tables, so it is cheap to create and to copy.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct GaloisField {
+    tables: &'static Tables,
+}
+
+/// Generate the log table of GF(2^bits) given an irreducible polynomial
+/// which maps the elements of the Galois field to their discrete
+/// logarithm. The field has 2^bits elements and the polynomial is given
+/// without its leading x^bits term. Since there is no log for 0 so the
+/// entry in 0th index can be ignored.
 /// # Arguments
 ///
-/// * `irre_poly` - An irreducible polynomial for GF(2^8)
+/// * `irre_poly` - An irreducible polynomial for GF(2^bits)
+/// * `bits` - Bit width of the elements of the field
 ///
 /// # Example
-/// ```
-/// use crate::galois::gen_log_table;
+/// ```ignore
+/// use crate::galois::gen_log_table_for;
 ///
-/// let log_table = gen_log_table(29);
+/// let log_table = gen_log_table_for(3, 4);
 /// ```
-pub(crate) fn gen_log_table(irre_poly: usize) -> [u8; FIELD_SIZE] {
-    let mut res = [0 as u8; FIELD_SIZE];
+pub(crate) fn gen_log_table_for(irre_poly: usize, bits: u32) -> Vec<usize> {
+    let field_size: usize = 1 << bits;
+    let mut res = vec![0; field_size];
     // Primitive element
     let mut b: usize = 1;
 
-    for log in 0..FIELD_SIZE - 1 {
-        res[b] = log as u8;
+    for log in 0..field_size - 1 {
+        res[b] = log;
 
         // raising power of the element
-        b = b << 1;
+        b <<= 1;
 
         // modulo the element so that it remain inside the field
-        if FIELD_SIZE <= b {
-            b = (b - FIELD_SIZE) ^ irre_poly;
+        if field_size <= b {
+            b = (b - field_size) ^ irre_poly;
         }
     }
 
     res
 }
+
+/// Generate the log table of GF(2^8) given an irreducible polynomial.
+/// # Arguments
+///
+/// * `irre_poly` - An irreducible polynomial for GF(2^8)
+///
+/// # Example
+/// ```ignore
+/// use crate::galois::gen_log_table;
+///
+/// let log_table = gen_log_table(29);
+/// ```
+pub(crate) fn gen_log_table(irre_poly: usize) -> [u8; FIELD_SIZE] {
+    let mut res = [0u8; FIELD_SIZE];
+    for (elem, log) in res.iter_mut().zip(gen_log_table_for(irre_poly, 8)) {
+        *elem = log as u8;
+    }
+
+    res
+}
+
 /// Generate the exp table given the log table. The exp table maps logarithms
 /// to elements of the Galois field.
 /// # Arguments
@@ -57,7 +122,7 @@ pub(crate) fn gen_log_table(irre_poly: usize) -> [u8; FIELD_SIZE] {
 /// * `log_table` - The log table for GF(2^8)
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// use crate::galois::gen_exp_table;
 /// use crate::galois::gen_log_table;
 ///
@@ -65,10 +130,10 @@ pub(crate) fn gen_log_table(irre_poly: usize) -> [u8; FIELD_SIZE] {
 /// let exp_table = gen_exp_table(&log_table);
 /// ```
 pub(crate) fn gen_exp_table(log_table: &[u8; FIELD_SIZE]) -> [u8; EXP_TABLE_SIZE] {
-    let mut res = [0 as u8; EXP_TABLE_SIZE];
+    let mut res = [0u8; EXP_TABLE_SIZE];
 
-    for i in 1..FIELD_SIZE {
-        let log = log_table[i] as usize;
+    for (i, &log) in log_table.iter().enumerate().skip(1) {
+        let log = log as usize;
         res[log] = i as u8;
         // Populating the repeated table
         res[log + FIELD_SIZE - 1] = i as u8;
@@ -77,23 +142,42 @@ pub(crate) fn gen_exp_table(log_table: &[u8; FIELD_SIZE]) -> [u8; EXP_TABLE_SIZE
     res
 }
 
+impl fmt::Debug for GaloisField {
+    // The tables are generated from the polynomial so print it instead.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GaloisField")
+            .field("polynomial", &(FIELD_SIZE | IRREDUCIBLE_POLYNOMIAL))
+            .finish()
+    }
+}
+
+impl Default for GaloisField {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GaloisField {
     /// Create a new GaloisField(2^8)
     ///
     /// # Example
     /// ```
-    /// use crate::galois::GaloisField;
+    /// use reed_solomon::galois::GaloisField;
     ///
     /// let gf8 = GaloisField::new();
     /// ```
-    pub(crate) fn new() -> GaloisField {
-        let log_table = gen_log_table(IRREDUCIBLE_POLYNOMIAL);
-        let exp_table = gen_exp_table(&log_table);
+    pub fn new() -> GaloisField {
+        let tables = TABLES.get_or_init(|| {
+            let log_table = gen_log_table(IRREDUCIBLE_POLYNOMIAL);
+            let exp_table = gen_exp_table(&log_table);
 
-        GaloisField {
-            log_table,
-            exp_table,
-        }
+            Tables {
+                log_table,
+                exp_table,
+            }
+        });
+
+        GaloisField { tables }
     }
 
     /// Adds 2 elements in the field.
@@ -103,7 +187,7 @@ impl GaloisField {
     /// * `b` - Second element to be added
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::galois::GaloisField;
     ///
     /// let res = GaloisField::add(1, 1);
@@ -120,19 +204,19 @@ impl GaloisField {
     ///
     /// # Example
     /// ```
-    /// use crate::galois::GaloisField;
+    /// use reed_solomon::galois::GaloisField;
     ///
     /// let gf8 = GaloisField::new();
-    /// let res = gf8.mul(1, 1);
+    /// assert_eq!(12, gf8.mul(3, 4));
     /// ```
-    pub(crate) fn mul(&self, a: u8, b: u8) -> u8 {
+    pub fn mul(&self, a: u8, b: u8) -> u8 {
         if a == 0 || b == 0 {
             0
         } else {
-            let log_a = self.log_table[a as usize];
-            let log_b = self.log_table[b as usize];
+            let log_a = self.tables.log_table[a as usize];
+            let log_b = self.tables.log_table[b as usize];
             let log_res = log_a as usize + log_b as usize;
-            self.exp_table[log_res]
+            self.tables.exp_table[log_res]
         }
     }
 
@@ -142,29 +226,148 @@ impl GaloisField {
     /// * `a` - dividend
     /// * `b` - divisor
     ///
+    /// # Panics
+    ///
+    /// If the divisor is 0.
+    ///
     /// # Example
     /// ```
-    /// use crate::galois::GaloisField;
+    /// use reed_solomon::galois::GaloisField;
     ///
     /// let gf8 = GaloisField::new();
-    /// let res = gf8.div(4, 2);
+    /// assert_eq!(2, gf8.div(4, 2));
     /// ```
-    pub(crate) fn div(&self, a: u8, b: u8) -> u8 {
+    pub fn div(&self, a: u8, b: u8) -> u8 {
         if a == 0 {
             0
         } else if b == 0 {
             panic!("Can't divide by 0");
         } else {
-            let log_a = self.log_table[a as usize];
-            let log_b = self.log_table[b as usize];
+            let log_a = self.tables.log_table[a as usize];
+            let log_b = self.tables.log_table[b as usize];
             let mut log_res = log_a as isize - log_b as isize;
             if log_res < 0 {
                 log_res += 255;
             }
-            self.exp_table[log_res as usize]
+            self.tables.exp_table[log_res as usize]
         }
     }
 
+    /// Multiplies every element of the input by a constant and writes the
+    /// products into the output, which has the same length as the input.
+    /// # Arguments
+    ///
+    /// * `c` - Constant the input is multiplied by
+    /// * `input` - Elements to be multiplied
+    /// * `output` - Products (to be overwritten)
+    ///
+    /// # Panics
+    ///
+    /// If the output and the input have different lengths.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let mut output = [0; 3];
+    /// gf8.mul_slice(3, &[1, 2, 4], &mut output);
+    /// assert_eq!([3, 6, 12], output);
+    /// ```
+    pub fn mul_slice(&self, c: u8, input: &[u8], output: &mut [u8]) {
+        assert_eq!(input.len(), output.len());
+        for (o, &elem) in output.iter_mut().zip(input.iter()) {
+            *o = self.mul(c, elem);
+        }
+    }
+
+    /// Multiplies every element of the input by a constant and adds the
+    /// products to the output, which has the same length as the input.
+    /// # Arguments
+    ///
+    /// * `c` - Constant the input is multiplied by
+    /// * `input` - Elements to be multiplied
+    /// * `output` - Sums of the products and the previous output
+    ///
+    /// # Panics
+    ///
+    /// If the output and the input have different lengths.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let mut output = [1, 1, 1];
+    /// gf8.mul_add_slice(3, &[1, 2, 4], &mut output);
+    /// assert_eq!([2, 7, 13], output);
+    /// ```
+    pub fn mul_add_slice(&self, c: u8, input: &[u8], output: &mut [u8]) {
+        assert_eq!(input.len(), output.len());
+        for (o, &elem) in output.iter_mut().zip(input.iter()) {
+            *o = Self::add(*o, self.mul(c, elem));
+        }
+    }
+
+    /// Returns the multiplicative inverse of an element, or None for 0
+    /// which has none.
+    /// # Arguments
+    ///
+    /// * `a` - Element to be inverted
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let inv = gf8.inv(3).unwrap();
+    /// assert_eq!(1, gf8.mul(3, inv));
+    /// ```
+    pub fn inv(&self, a: u8) -> Option<u8> {
+        if a == 0 {
+            return None;
+        }
+        let log_a = self.tables.log_table[a as usize] as usize;
+
+        Some(self.tables.exp_table[(255 - log_a) % 255])
+    }
+
+    /// Returns the multiplicative inverses of all the given elements with
+    /// a single inversion (Montgomery's trick), or None if any of them is
+    /// 0. Besides the inversion it only needs 3 multiplications per
+    /// element.
+    /// # Arguments
+    ///
+    /// * `elems` - Elements to be inverted
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let invs = gf8.batch_inv(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec![gf8.inv(1).unwrap(), gf8.inv(2).unwrap(), gf8.inv(3).unwrap()], invs);
+    /// ```
+    pub fn batch_inv(&self, elems: &[u8]) -> Option<Vec<u8>> {
+        // prefix[i] is the product of the elements before i.
+        let mut prefix: Vec<u8> = Vec::with_capacity(elems.len());
+        let mut product = 1;
+        for &elem in elems.iter() {
+            prefix.push(product);
+            product = self.mul(product, elem);
+        }
+
+        // Peel the elements off the inverse of the product from the back.
+        let mut inv_product = self.inv(product)?;
+        let mut invs = vec![0; elems.len()];
+        for i in (0..elems.len()).rev() {
+            invs[i] = self.mul(inv_product, prefix[i]);
+            inv_product = self.mul(inv_product, elems[i]);
+        }
+
+        Some(invs)
+    }
+
     /// Computes a^n in Galois field
     /// # Arguments
     ///
@@ -172,26 +375,339 @@ impl GaloisField {
     /// * `n` - Exponent element
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::galois::GaloisField;
     ///
     /// let gf8 = GaloisField::new();
     /// let res = gf8.exp(2, 2);
     /// ```
+    #[allow(dead_code)]
     pub(crate) fn exp(&self, a: u8, n: usize) -> u8 {
         if n == 0 {
             1
         } else if a == 0 {
             0
         } else {
-            let log_a = self.log_table[a as usize];
+            let log_a = self.tables.log_table[a as usize];
             let mut log_res = log_a as usize * n;
             while 255 <= log_res {
                 log_res -= 255;
             }
-            self.exp_table[log_res]
+            self.tables.exp_table[log_res]
+        }
+    }
+
+    /// Returns the full multiplication table of the field, where the
+    /// element in row a and column b is a * b. It can be compared with a
+    /// reference implementation of GF(2^8) with the same polynomial.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let table = gf8.cayley_table();
+    /// assert_eq!(12, table[3][4]);
+    /// ```
+    pub fn cayley_table(&self) -> Vec<Vec<u8>> {
+        (0..FIELD_SIZE)
+            .map(|a| {
+                let mut row = [0; FIELD_SIZE];
+                self.fill_mul_row(a as u8, &mut row);
+                row.to_vec()
+            })
+            .collect()
+    }
+
+    /// Fills the given row with the products of the given element and
+    /// every element of the field, i.e. row a of the multiplication table.
+    /// # Arguments
+    ///
+    /// * `a` - Element every element is multiplied by
+    /// * `out` - Products (to be overwritten), one per element of the field
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::{GaloisField, FIELD_SIZE};
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let mut row = [0; FIELD_SIZE];
+    /// gf8.fill_mul_row(3, &mut row);
+    /// ```
+    pub(crate) fn fill_mul_row(&self, a: u8, out: &mut [u8; FIELD_SIZE]) {
+        for (b, product) in out.iter_mut().enumerate() {
+            *product = self.mul(a, b as u8);
         }
     }
+
+    /// Returns the implementation the field multiplies elements with. The
+    /// field has no SIMD implementations, so it is always
+    /// `Kernel::Scalar`. The shards are multiplied by the kernel of the
+    /// code, which `ReedSolomon::active_kernel` reports.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::{GaloisField, Kernel};
+    ///
+    /// let gf8 = GaloisField::new();
+    /// assert_eq!(Kernel::Scalar, gf8.active_kernel());
+    /// ```
+    pub fn active_kernel(&self) -> Kernel {
+        Kernel::Scalar
+    }
+
+    /// Times the given no. of multiplications in the Galois field, so
+    /// that callers can compare configurations at runtime. It is not
+    /// used by encoding or decoding.
+    /// # Arguments
+    ///
+    /// * `iterations` - No. of multiplications to be timed
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    ///
+    /// let elapsed = GaloisField::benchmark_mul(1000);
+    /// ```
+    pub fn benchmark_mul(iterations: usize) -> Duration {
+        let gf8 = GaloisField::new();
+        let mut value: u8 = 1;
+
+        let start = Instant::now();
+        for i in 0..iterations {
+            // Keep both operands non-zero so the table lookups are not skipped.
+            value = gf8.mul(black_box(value), black_box(i as u8 | 1)) | 1;
+        }
+        black_box(value);
+
+        start.elapsed()
+    }
+
+    /// Fills the given row with the successive powers of base in Galois
+    /// field i.e. out[c] = base^c. Every power is computed from the
+    /// previous one with a single multiplication instead of a separate
+    /// exponentiation per element.
+    /// # Arguments
+    ///
+    /// * `base` - Base element
+    /// * `out` - Row to be filled with the powers of base
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField;
+    ///
+    /// let gf8 = GaloisField::new();
+    /// let mut row = [0; 4];
+    /// gf8.fill_vandermonde_row(2, &mut row);
+    /// ```
+    pub(crate) fn fill_vandermonde_row(&self, base: u8, out: &mut [u8]) {
+        let mut value: u8 = 1;
+        for elem in out.iter_mut() {
+            *elem = value;
+            value = self.mul(value, base);
+        }
+    }
+}
+
+/// The irreducible polynomial of GF(2^16) used by PAR2, without its
+/// leading x^16 term i.e. 0x1100B.
+pub(crate) const IRREDUCIBLE_POLYNOMIAL_16: usize = 0x100B;
+
+/// This size of GF(2^16) i.e. 2^16.
+pub(crate) const FIELD_SIZE_16: usize = 1 << 16;
+
+/// A Struct to represent GF(2^16). The tables are too big to be copied
+/// around like the ones of GF(2^8), so they are allocated on the heap.
+#[derive(Clone)]
+pub(crate) struct GaloisField16 {
+    log_table: Vec<u16>,
+    exp_table: Vec<u16>,
+}
+
+impl GaloisField16 {
+    /// Create a new GaloisField(2^16)
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// ```
+    pub(crate) fn new() -> GaloisField16 {
+        let log_table: Vec<u16> = gen_log_table_for(IRREDUCIBLE_POLYNOMIAL_16, 16)
+            .into_iter()
+            .map(|log| log as u16)
+            .collect();
+        // The table is repeated a 2nd time so fn mul() doesn't have to
+        // check bounds.
+        let mut exp_table: Vec<u16> = vec![0; FIELD_SIZE_16 * 2 - 2];
+        for (i, &log) in log_table.iter().enumerate().skip(1) {
+            let log = log as usize;
+            exp_table[log] = i as u16;
+            exp_table[log + FIELD_SIZE_16 - 1] = i as u16;
+        }
+
+        GaloisField16 {
+            log_table,
+            exp_table,
+        }
+    }
+
+    /// Multiplies 2 elements in the field.
+    /// # Arguments
+    ///
+    /// * `a` - First element to be multiplied
+    /// * `b` - Second element to be multiplied
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// let res = gf16.mul(1, 1);
+    /// ```
+    pub(crate) fn mul(&self, a: u16, b: u16) -> u16 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            let log_a = self.log_table[a as usize] as usize;
+            let log_b = self.log_table[b as usize] as usize;
+            self.exp_table[log_a + log_b]
+        }
+    }
+
+    /// Divides 2 elements in the field.
+    /// # Arguments
+    ///
+    /// * `a` - dividend
+    /// * `b` - divisor
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// let res = gf16.div(4, 2);
+    /// ```
+    pub(crate) fn div(&self, a: u16, b: u16) -> u16 {
+        if a == 0 {
+            0
+        } else if b == 0 {
+            panic!("Can't divide by 0");
+        } else {
+            let log_a = self.log_table[a as usize] as usize;
+            let log_b = self.log_table[b as usize] as usize;
+            self.exp_table[log_a + FIELD_SIZE_16 - 1 - log_b]
+        }
+    }
+
+    /// Computes 2^n in the field, i.e. the element whose log is n.
+    /// # Arguments
+    ///
+    /// * `n` - Exponent
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// let res = gf16.exp2(3);
+    /// ```
+    pub(crate) fn exp2(&self, n: usize) -> u16 {
+        self.exp_table[n % (FIELD_SIZE_16 - 1)]
+    }
+
+    /// Computes a^n in the field.
+    /// # Arguments
+    ///
+    /// * `a` - Base element
+    /// * `n` - Exponent element
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// let res = gf16.exp(2, 2);
+    /// ```
+    pub(crate) fn exp(&self, a: u16, n: usize) -> u16 {
+        if n == 0 {
+            1
+        } else if a == 0 {
+            0
+        } else {
+            let log_a = self.log_table[a as usize] as usize;
+            self.exp2(log_a * (n % (FIELD_SIZE_16 - 1)))
+        }
+    }
+
+    /// Multiplies every 16 bit little endian word of the input by the
+    /// coefficient and adds the products to the output.
+    /// # Arguments
+    ///
+    /// * `coefficient` - Element the input is multiplied by
+    /// * `input` - Words to be multiplied
+    /// * `output` - Sums of the products and the previous output
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// let mut output = [0; 4];
+    /// gf16.mul_add_words(3, &[1, 0, 2, 0], &mut output);
+    /// ```
+    pub(crate) fn mul_add_words(&self, coefficient: u16, input: &[u8], output: &mut [u8]) {
+        for (o, i) in output.chunks_exact_mut(2).zip(input.chunks_exact(2)) {
+            let word = u16::from_le_bytes([i[0], i[1]]);
+            let sum = u16::from_le_bytes([o[0], o[1]]) ^ self.mul(coefficient, word);
+            o.copy_from_slice(&sum.to_le_bytes());
+        }
+    }
+
+    /// Inverts a square matrix by Gauss-Jordan elimination.
+    /// Returns None if the matrix is singular.
+    /// # Arguments
+    ///
+    /// * `matrix` - Rows of the matrix
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField16;
+    ///
+    /// let gf16 = GaloisField16::new();
+    /// let inverse = gf16.invert_matrix(vec![vec![1, 1], vec![1, 2]]);
+    /// ```
+    pub(crate) fn invert_matrix(&self, matrix: Vec<Vec<u16>>) -> Option<Vec<Vec<u16>>> {
+        let size = matrix.len();
+        let mut left = matrix;
+        let mut right: Vec<Vec<u16>> = (0..size)
+            .map(|r| (0..size).map(|c| (r == c) as u16).collect())
+            .collect();
+
+        for c in 0..size {
+            let pivot = (c..size).find(|&r| left[r][c] != 0)?;
+            left.swap(c, pivot);
+            right.swap(c, pivot);
+
+            let scale = self.div(1, left[c][c]);
+            for elem in left[c].iter_mut().chain(right[c].iter_mut()) {
+                *elem = self.mul(*elem, scale);
+            }
+            for r in 0..size {
+                let factor = left[r][c];
+                if r == c || factor == 0 {
+                    continue;
+                }
+                for k in 0..size {
+                    left[r][k] ^= self.mul(factor, left[c][k]);
+                    right[r][k] ^= self.mul(factor, right[c][k]);
+                }
+            }
+        }
+
+        Some(right)
+    }
 }
 
 #[cfg(test)]
@@ -253,6 +769,36 @@ mod tests {
         }
     }
     #[test]
+    fn test_gen_log_table_gf16() {
+        // x^4 + x + 1 is irreducible for GF(2^4).
+        let res = gen_log_table_for(3, 4);
+        assert_eq!(16, res.len());
+
+        // The powers of the primitive element go through every non zero
+        // element exactly once, so the logs are a permutation of 0..15.
+        let mut logs: Vec<usize> = res[1..].to_vec();
+        logs.sort();
+        assert_eq!((0..15).collect::<Vec<usize>>(), logs);
+
+        // The product of elements is given by the sum of their logs.
+        let mut exp = [0; 15];
+        for elem in 1..16 {
+            exp[res[elem]] = elem;
+        }
+        assert_eq!(1, exp[0]);
+        assert_eq!(2, exp[1]);
+        // x^4 = x + 1
+        assert_eq!(3, exp[4]);
+        for a in 1..16 {
+            // Multiplying by x is a shift and a reduction.
+            let mut shifted = a << 1;
+            if shifted >= 16 {
+                shifted = (shifted - 16) ^ 3;
+            }
+            assert_eq!(shifted, exp[(res[a] + 1) % 15]);
+        }
+    }
+    #[test]
     fn test_gen_exp_table() {
         let log_table = gen_log_table(IRREDUCIBLE_POLYNOMIAL);
         let res = gen_exp_table(&log_table);
@@ -263,12 +809,8 @@ mod tests {
     #[test]
     fn test_gf_new() {
         let gf8 = GaloisField::new();
-        for i in 0..FIELD_SIZE {
-            assert_eq!(EXPECTED_LOG_RES[i], gf8.log_table[i]);
-        }
-        for i in 0..EXP_TABLE_SIZE {
-            assert_eq!(EXPECTED_EXP_RES[i], gf8.exp_table[i]);
-        }
+        assert_eq!(EXPECTED_LOG_RES, gf8.tables.log_table);
+        assert_eq!(EXPECTED_EXP_RES, gf8.tables.exp_table);
     }
     #[test]
     fn test_add() {
@@ -284,6 +826,46 @@ mod tests {
         assert_eq!(41, gf8.mul(23, 45));
     }
     #[test]
+    fn test_exp_table_boundary() {
+        let gf8 = GaloisField::new();
+        // 142 is 2^254, the inverse of the primitive element 2.
+        assert_eq!(142, gf8.tables.exp_table[254]);
+        // The repeated table starts at 255 with 2^0.
+        assert_eq!(1, gf8.tables.exp_table[255]);
+        assert_eq!(2, gf8.tables.exp_table[256]);
+        assert_eq!(142, gf8.tables.exp_table[EXP_TABLE_SIZE - 1]);
+        for log in 0..FIELD_SIZE - 1 {
+            assert_eq!(
+                gf8.tables.exp_table[log],
+                gf8.tables.exp_table[log + FIELD_SIZE - 1]
+            );
+        }
+
+        // Both logs are 254, so the product is read from index 508.
+        assert_eq!(254, gf8.tables.log_table[142]);
+        assert_eq!(71, gf8.mul(142, 142));
+        assert_eq!(1, gf8.mul(71, 4));
+        assert_eq!(1, gf8.mul(142, 2));
+    }
+    #[test]
+    fn test_cayley_table() {
+        let gf8 = GaloisField::new();
+        let table = gf8.cayley_table();
+        assert_eq!(FIELD_SIZE, table.len());
+        for row in table.iter() {
+            assert_eq!(FIELD_SIZE, row.len());
+        }
+        assert_eq!(12, table[3][4]);
+        assert_eq!(21, table[7][7]);
+        assert_eq!(41, table[23][45]);
+        assert_eq!(41, table[45][23]);
+        assert_eq!(71, table[142][142]);
+        for (a, row) in table.iter().enumerate() {
+            assert_eq!(0, row[0]);
+            assert_eq!(a as u8, row[1]);
+        }
+    }
+    #[test]
     fn test_exp() {
         let gf8 = GaloisField::new();
         assert_eq!(4, gf8.exp(2, 2));
@@ -297,4 +879,93 @@ mod tests {
         assert_eq!(16, gf8.div(128, 8));
         assert_eq!(33, gf8.div(99, 3));
     }
+    #[test]
+    fn test_mul_slice() {
+        let gf8 = GaloisField::new();
+        let input: Vec<u8> = (0..=255).collect();
+        let mut products = vec![0xFF; 256];
+        gf8.mul_slice(29, &input, &mut products);
+        let mut sums = input.clone();
+        gf8.mul_add_slice(29, &input, &mut sums);
+        for (i, &elem) in input.iter().enumerate() {
+            assert_eq!(gf8.mul(29, elem), products[i]);
+            assert_eq!(GaloisField::add(elem, products[i]), sums[i]);
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn test_mul_slice_len_mismatch() {
+        let gf8 = GaloisField::new();
+        let mut output = [0; 2];
+        gf8.mul_slice(3, &[1, 2, 4], &mut output);
+    }
+    #[test]
+    fn test_inv() {
+        let gf8 = GaloisField::new();
+        assert_eq!(None, gf8.inv(0));
+        for a in 1..FIELD_SIZE {
+            match gf8.inv(a as u8) {
+                Some(x) => assert_eq!(gf8.div(1, a as u8), x),
+                None => panic!("Every non-zero element has an inverse"),
+            }
+        }
+    }
+    #[test]
+    fn test_batch_inv() {
+        let gf8 = GaloisField::new();
+        let elems: Vec<u8> = (1..=255).collect();
+        match gf8.batch_inv(&elems) {
+            Some(x) => {
+                for (&elem, &inv) in elems.iter().zip(x.iter()) {
+                    assert_eq!(1, gf8.mul(elem, inv));
+                }
+            }
+            None => panic!("Every non-zero element has an inverse"),
+        }
+        assert_eq!(Some(vec![]), gf8.batch_inv(&[]));
+        assert_eq!(None, gf8.batch_inv(&[1, 0, 2]));
+    }
+    #[test]
+    fn test_fill_vandermonde_row() {
+        let gf8 = GaloisField::new();
+        let mut row = [0; 32];
+        for base in 0..FIELD_SIZE {
+            gf8.fill_vandermonde_row(base as u8, &mut row);
+            for (c, &elem) in row.iter().enumerate() {
+                assert_eq!(gf8.exp(base as u8, c), elem);
+            }
+        }
+    }
+    #[test]
+    fn test_benchmark_mul() {
+        let elapsed = GaloisField::benchmark_mul(1000);
+        assert!(elapsed > Duration::ZERO);
+        assert!(elapsed < Duration::from_secs(1));
+    }
+    #[test]
+    fn test_active_kernel() {
+        let gf8 = GaloisField::new();
+        assert_eq!(Kernel::Scalar, gf8.active_kernel());
+    }
+    #[test]
+    fn test_galois_field_16() {
+        let gf16 = GaloisField16::new();
+        assert_eq!(0x8000, gf16.exp2(15));
+        // x^16 is reduced by the polynomial 0x1100B.
+        assert_eq!(0x100B, gf16.exp2(16));
+        assert_eq!(gf16.exp2(16), gf16.mul(0x8000, 2));
+        assert_eq!(1, gf16.exp2(FIELD_SIZE_16 - 1));
+        for a in [1u16, 2, 0x1234, 0xFFFF] {
+            assert_eq!(1, gf16.mul(a, gf16.div(1, a)));
+            assert_eq!(gf16.mul(a, gf16.mul(a, a)), gf16.exp(a, 3));
+        }
+        assert_eq!(0, gf16.mul(0, 0x1234));
+    }
+    #[test]
+    fn test_shared_tables() {
+        let first = GaloisField::new();
+        let second = GaloisField::new();
+        assert!(std::ptr::eq(first.tables, second.tables));
+        assert_eq!(first, second);
+    }
 }
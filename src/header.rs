@@ -0,0 +1,239 @@
+/// Size of a u64 header field in bytes.
+pub(crate) const U64_LEN: usize = 8;
+
+/// Write the value into the first 8 bytes of the buffer in big-endian
+/// byte order. All multi-byte header fields are big-endian so that
+/// headers are portable across architectures.
+/// # Arguments
+///
+/// * `buf` - Buffer of at least 8 bytes
+/// * `value` - Value to be written
+///
+/// # Example
+/// ```ignore
+/// use crate::header::write_u64_be;
+///
+/// let mut buf = [0; 8];
+/// write_u64_be(&mut buf, 256);
+/// ```
+pub(crate) fn write_u64_be(buf: &mut [u8], value: u64) {
+    buf[..U64_LEN].copy_from_slice(&value.to_be_bytes());
+}
+
+/// Read a value from the first 8 bytes of the buffer in big-endian
+/// byte order.
+/// # Arguments
+///
+/// * `buf` - Buffer of at least 8 bytes
+///
+/// # Example
+/// ```ignore
+/// use crate::header::read_u64_be;
+///
+/// let value = read_u64_be(&[0, 0, 0, 0, 0, 0, 1, 0]);
+/// ```
+pub(crate) fn read_u64_be(buf: &[u8]) -> u64 {
+    let mut bytes = [0u8; U64_LEN];
+    bytes.copy_from_slice(&buf[..U64_LEN]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Encode the value as a LEB128 varint with the bytes in reverse order,
+/// so that it can be read backwards from the end of a buffer: the last
+/// byte holds the lowest 7 bits and every byte but the first one has the
+/// continuation bit set.
+/// # Arguments
+///
+/// * `value` - Value to be encoded
+///
+/// # Example
+/// ```ignore
+/// use crate::header::encode_trailing_varint;
+///
+/// assert_eq!(vec![0x02, 0x80], encode_trailing_varint(256));
+/// ```
+pub(crate) fn encode_trailing_varint(value: u64) -> Vec<u8> {
+    let mut res: Vec<u8> = vec![];
+    let mut value = value;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            res.push(byte);
+            break;
+        }
+        res.push(byte | 0x80);
+    }
+    res.reverse();
+
+    res
+}
+
+/// Read a varint written by `encode_trailing_varint` from the end of
+/// the buffer. Returns the value and the no. of bytes it took, or None if
+/// the buffer ends in the middle of it or it doesn't fit in a u64.
+/// # Arguments
+///
+/// * `buf` - Buffer ending with the varint
+///
+/// # Example
+/// ```ignore
+/// use crate::header::read_trailing_varint;
+///
+/// assert_eq!(Some((256, 2)), read_trailing_varint(&[7, 0x02, 0x80]));
+/// ```
+pub(crate) fn read_trailing_varint(buf: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    for (i, &byte) in buf.iter().rev().enumerate() {
+        let shift = 7 * i as u32;
+        let bits = (byte & 0x7F) as u64;
+        if shift >= u64::BITS || (bits << shift) >> shift != bits {
+            return None;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+
+    None
+}
+
+/// Magic bytes at the start of every archived shard.
+pub(crate) const ARCHIVE_MAGIC: [u8; 4] = *b"RSA1";
+
+/// Size of the header of every archived shard: the magic bytes followed
+/// by the shard index, the no. of data shards, the no. of parity shards
+/// and the length of the archived data.
+pub(crate) const ARCHIVE_HEADER_LEN: usize = ARCHIVE_MAGIC.len() + 4 * U64_LEN;
+
+/// Fields of the header of an archived shard.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ArchiveHeader {
+    pub(crate) index: u64,
+    pub(crate) data_shards: u64,
+    pub(crate) parity_shards: u64,
+    pub(crate) data_len: u64,
+}
+
+impl ArchiveHeader {
+    /// Write the header into the start of the buffer.
+    /// # Arguments
+    ///
+    /// * `buf` - Buffer of at least ARCHIVE_HEADER_LEN bytes
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::header::{ArchiveHeader, ARCHIVE_HEADER_LEN};
+    ///
+    /// let header = ArchiveHeader { index: 0, data_shards: 4, parity_shards: 2, data_len: 10 };
+    /// let mut buf = [0; ARCHIVE_HEADER_LEN];
+    /// header.write(&mut buf);
+    /// ```
+    pub(crate) fn write(&self, buf: &mut [u8]) {
+        let (magic, fields) = buf.split_at_mut(ARCHIVE_MAGIC.len());
+        magic.copy_from_slice(&ARCHIVE_MAGIC);
+        let values = [
+            self.index,
+            self.data_shards,
+            self.parity_shards,
+            self.data_len,
+        ];
+        for (field, value) in fields.chunks_mut(U64_LEN).zip(values.iter()) {
+            write_u64_be(field, *value);
+        }
+    }
+
+    /// Read the header from the start of the buffer. Returns None if the
+    /// buffer is too short or doesn't start with the magic bytes.
+    /// # Arguments
+    ///
+    /// * `buf` - Archived shard
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::header::ArchiveHeader;
+    ///
+    /// let header = ArchiveHeader::read(&shard);
+    /// ```
+    pub(crate) fn read(buf: &[u8]) -> Option<ArchiveHeader> {
+        if buf.len() < ARCHIVE_HEADER_LEN || buf[..ARCHIVE_MAGIC.len()] != ARCHIVE_MAGIC {
+            return None;
+        }
+
+        let field = |i: usize| read_u64_be(&buf[ARCHIVE_MAGIC.len() + i * U64_LEN..]);
+        Some(ArchiveHeader {
+            index: field(0),
+            data_shards: field(1),
+            parity_shards: field(2),
+            data_len: field(3),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_u64_be() {
+        let mut buf = [0; 10];
+        write_u64_be(&mut buf, 0x0102_0304_0506_0708);
+        assert_eq!([1, 2, 3, 4, 5, 6, 7, 8, 0, 0], buf);
+    }
+    #[test]
+    fn test_read_u64_be() {
+        // The least significant byte comes last, whatever the byte
+        // order of the machine is.
+        assert_eq!(256, read_u64_be(&[0, 0, 0, 0, 0, 0, 1, 0]));
+        assert_eq!(1, read_u64_be(&[0, 0, 0, 0, 0, 0, 0, 1, 255]));
+        assert_eq!(
+            0x0102_0304_0506_0708,
+            read_u64_be(&[1, 2, 3, 4, 5, 6, 7, 8])
+        );
+
+        // The little-endian bytes of 1 read as big-endian.
+        assert_eq!(1 << 56, read_u64_be(&1u64.to_le_bytes()));
+    }
+    #[test]
+    fn test_archive_header() {
+        let header = ArchiveHeader {
+            index: 1,
+            data_shards: 4,
+            parity_shards: 2,
+            data_len: 258,
+        };
+        let mut buf = [0; ARCHIVE_HEADER_LEN + 1];
+        header.write(&mut buf);
+        assert_eq!(
+            [
+                b'R', b'S', b'A', b'1', 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0,
+                0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 1, 2, 0
+            ],
+            buf
+        );
+        assert_eq!(Some(header), ArchiveHeader::read(&buf));
+
+        assert_eq!(None, ArchiveHeader::read(&buf[..ARCHIVE_HEADER_LEN - 1]));
+        buf[0] = b'X';
+        assert_eq!(None, ArchiveHeader::read(&buf));
+    }
+    #[test]
+    fn test_trailing_varint() {
+        assert_eq!(vec![0], encode_trailing_varint(0));
+        assert_eq!(vec![0x7F], encode_trailing_varint(127));
+        assert_eq!(vec![0x01, 0x80], encode_trailing_varint(128));
+        assert_eq!(vec![0x02, 0x80], encode_trailing_varint(256));
+
+        for value in [0, 1, 127, 128, 300, 1 << 35, u64::MAX] {
+            let mut buf = vec![0xFF, 0x00];
+            let varint = encode_trailing_varint(value);
+            buf.extend_from_slice(&varint);
+            assert_eq!(Some((value, varint.len())), read_trailing_varint(&buf));
+        }
+
+        assert_eq!(None, read_trailing_varint(&[]));
+        assert_eq!(None, read_trailing_varint(&[0x81, 0x80]));
+        assert_eq!(None, read_trailing_varint(&[0xFF; 11]));
+    }
+}
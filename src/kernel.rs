@@ -0,0 +1,186 @@
+use crate::galois::{GaloisField, Kernel, FIELD_SIZE};
+use std::sync::OnceLock;
+
+/// Product of every pair of elements, indexed by the scalar and then the
+/// element. It is 64 KiB, so it is only built when a FullTableKernel is
+/// created, and shared by all of them.
+static MUL_TABLE: OnceLock<Box<[[u8; FIELD_SIZE]]>> = OnceLock::new();
+
+/// Multiplication of a slice by an element of the Galois Field, which is
+/// the inner loop of encoding and decoding. Implement it to offload the
+/// multiplication e.g. to a GPU and pass it to
+/// `ReedSolomonBuilder::kernel`.
+pub trait MulKernel {
+    /// Multiplies every byte of the input by the scalar and writes the
+    /// products into the output, which has the same length as the input.
+    /// # Arguments
+    ///
+    /// * `scalar` - Element the input is multiplied by
+    /// * `input` - Elements to be multiplied
+    /// * `output` - Products (to be overwritten)
+    fn mul_slice(&self, scalar: u8, input: &[u8], output: &mut [u8]);
+
+    /// Multiplies every byte of the input by the scalar and adds the
+    /// products to the output, which has the same length as the input.
+    /// The default implementation calls `mul_slice` on a temporary buffer.
+    /// # Arguments
+    ///
+    /// * `scalar` - Element the input is multiplied by
+    /// * `input` - Elements to be multiplied
+    /// * `output` - Sums of the products and the previous output
+    fn mul_slice_add(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+        let mut products: Vec<u8> = vec![0; output.len()];
+        self.mul_slice(scalar, input, &mut products);
+        for (o, &product) in output.iter_mut().zip(products.iter()) {
+            *o = GaloisField::add(*o, product);
+        }
+    }
+
+    /// Returns the implementation the kernel multiplies with, as reported
+    /// by `ReedSolomon::active_kernel`. It defaults to `Kernel::Custom`.
+    fn kind(&self) -> Kernel {
+        Kernel::Custom
+    }
+}
+
+/// The default kernel, which multiplies through the log and exp tables
+/// one byte at a time.
+#[derive(Default)]
+pub struct TableKernel {
+    gf: GaloisField,
+}
+
+impl TableKernel {
+    /// Create a new TableKernel.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::kernel::TableKernel;
+    ///
+    /// let kernel = TableKernel::new();
+    /// ```
+    pub fn new() -> TableKernel {
+        TableKernel {
+            gf: GaloisField::new(),
+        }
+    }
+}
+
+impl MulKernel for TableKernel {
+    fn mul_slice(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+        self.gf.mul_slice(scalar, input, output);
+    }
+
+    fn mul_slice_add(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+        self.gf.mul_add_slice(scalar, input, output);
+    }
+
+    fn kind(&self) -> Kernel {
+        Kernel::Scalar
+    }
+}
+
+/// A kernel which multiplies through the full multiplication table of the
+/// field, so every product is a single lookup without the zero checks and
+/// the addition of the log and exp tables. It trades 64 KiB of memory,
+/// shared by all instances, for speed.
+pub struct FullTableKernel {
+    table: &'static [[u8; FIELD_SIZE]],
+}
+
+impl FullTableKernel {
+    /// Create a new FullTableKernel, building the table on first use.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::kernel::FullTableKernel;
+    ///
+    /// let kernel = FullTableKernel::new();
+    /// ```
+    pub fn new() -> FullTableKernel {
+        let table = MUL_TABLE.get_or_init(|| {
+            let gf = GaloisField::new();
+            let mut table = vec![[0; FIELD_SIZE]; FIELD_SIZE];
+            for (a, row) in table.iter_mut().enumerate() {
+                gf.fill_mul_row(a as u8, row);
+            }
+
+            table.into_boxed_slice()
+        });
+
+        FullTableKernel { table }
+    }
+}
+
+impl Default for FullTableKernel {
+    fn default() -> Self {
+        FullTableKernel::new()
+    }
+}
+
+impl MulKernel for FullTableKernel {
+    fn mul_slice(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+        let row = &self.table[scalar as usize];
+        for (o, &elem) in output.iter_mut().zip(input.iter()) {
+            *o = row[elem as usize];
+        }
+    }
+
+    fn mul_slice_add(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+        let row = &self.table[scalar as usize];
+        for (o, &elem) in output.iter_mut().zip(input.iter()) {
+            *o = GaloisField::add(*o, row[elem as usize]);
+        }
+    }
+
+    fn kind(&self) -> Kernel {
+        Kernel::FullTable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kernel relying on the default `mul_slice_add`.
+    struct MulOnlyKernel(TableKernel);
+
+    impl MulKernel for MulOnlyKernel {
+        fn mul_slice(&self, scalar: u8, input: &[u8], output: &mut [u8]) {
+            self.0.mul_slice(scalar, input, output);
+        }
+    }
+
+    #[test]
+    fn test_mul_slice() {
+        let kernel = TableKernel::new();
+        let mut output = [0; 3];
+        kernel.mul_slice(3, &[4, 0, 1], &mut output);
+        assert_eq!([12, 0, 3], output);
+
+        kernel.mul_slice_add(7, &[7, 1, 0], &mut output);
+        assert_eq!([12 ^ 21, 7, 3], output);
+
+        let mut default_output = [12, 0, 3];
+        MulOnlyKernel(TableKernel::new()).mul_slice_add(7, &[7, 1, 0], &mut default_output);
+        assert_eq!(output, default_output);
+    }
+
+    #[test]
+    fn test_full_table_kernel() {
+        let table_kernel = TableKernel::new();
+        let full_kernel = FullTableKernel::new();
+        let input: Vec<u8> = (0..=255).collect();
+        for scalar in 0..=255 {
+            let mut expected = input.clone();
+            let mut output = input.clone();
+            table_kernel.mul_slice(scalar, &input, &mut expected);
+            full_kernel.mul_slice(scalar, &input, &mut output);
+            assert_eq!(expected, output);
+
+            table_kernel.mul_slice_add(scalar, &input, &mut expected);
+            full_kernel.mul_slice_add(scalar, &input, &mut output);
+            assert_eq!(expected, output);
+        }
+    }
+}
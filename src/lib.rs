@@ -1,12 +1,108 @@
+pub mod adaptive;
+pub mod buffer;
+pub mod builder;
+mod cache;
+mod checksum;
+#[cfg(feature = "compat")]
+pub mod compat;
 pub mod error;
+pub mod fixed;
 pub mod galois;
+mod header;
+pub mod kernel;
 pub mod matrix;
+pub mod par2;
+pub mod product;
+pub mod profiles;
+mod progress;
+pub mod rs16;
+pub mod scratch;
+#[cfg(feature = "bytes")]
+mod shard_bytes;
+pub mod shard_set;
+pub mod striper;
+pub mod verifier;
+mod welch;
+mod whiten;
 
+pub use crate::builder::{MatrixKind, ReedSolomonBuilder};
+use crate::cache::{DecodeCache, DEFAULT_DECODE_CACHE_SIZE};
 use crate::error::Error;
-use crate::galois::GaloisField;
+pub use crate::fixed::ReedSolomonConst;
+use crate::galois::{GaloisField, Kernel, FIELD_SIZE};
+use crate::kernel::{MulKernel, TableKernel};
 use crate::matrix::Matrix;
+pub use crate::rs16::ReedSolomon16;
+pub use crate::shard_set::ShardSet;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The role of the shard at some position of a custom shard layout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShardRole {
+    /// The data shard of the given index.
+    Data(usize),
+    /// The parity shard of the given index.
+    Parity(usize),
+}
+
+/// The no. of shards of a code, as returned by `ReedSolomon::geometry`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Geometry {
+    /// No. of data shards.
+    pub data_shards: usize,
+    /// No. of parity shards.
+    pub parity_shards: usize,
+    /// Total no. of shards, i.e. data shards plus parity shards.
+    pub total_shards: usize,
+}
+
+/// All the shards of one stripe, data shards followed by parity shards,
+/// as taken by `ReedSolomon::encode_batch`.
+pub type Stripe = Vec<Vec<u8>>;
+
+/// The result of `ReedSolomon::verify_with_report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Indices of the parity shards which don't match the data shards,
+    /// counted from the first parity shard.
+    pub mismatched_parity: Vec<usize>,
+    /// Indices of the shards whose corruption alone explains the
+    /// mismatches. None if there are too few parity shards to tell.
+    pub suspects: Option<Vec<usize>>,
+}
+
+impl VerifyReport {
+    /// Returns whether the parity shards match the data shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::VerifyReport;
+    ///
+    /// let report = VerifyReport { mismatched_parity: vec![], suspects: Some(vec![]) };
+    /// assert!(report.is_consistent());
+    /// ```
+    pub fn is_consistent(&self) -> bool {
+        self.mismatched_parity.is_empty()
+    }
+}
 
 /// A Struct to represent and store data for Reed Solomon Erasure Coding.
+/// Two codes are equal if they have the same shard counts and matrices,
+/// whatever kernel they multiply with.
+///
+/// A code is `Send + Sync` and every method takes `&self`, so one
+/// `Arc<ReedSolomon>` can be shared by a pool of threads. The only state
+/// shared between calls is the decode cache, which is behind a mutex, and
+/// the evaluation points of `correct`, which are computed once. Neither
+/// ever changes the result of a call.
+#[derive(Clone)]
 pub struct ReedSolomon {
     data_shard_count: usize,
     parity_shard_count: usize,
@@ -14,9 +110,47 @@ pub struct ReedSolomon {
     parity: Matrix,
     gf: GaloisField,
     matrix: Matrix,
+    systematic: bool,
+    kernel: Arc<dyn MulKernel + Send + Sync>,
+    decode_cache: Arc<Mutex<DecodeCache>>,
+    // None if the encoding matrix isn't built from the Vandermonde matrix.
+    evaluation_points: OnceLock<Option<Vec<u8>>>,
+}
+
+impl fmt::Debug for ReedSolomon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReedSolomon")
+            .field("data_shard_count", &self.data_shard_count)
+            .field("parity_shard_count", &self.parity_shard_count)
+            .field("systematic", &self.systematic)
+            .field("gf", &self.gf)
+            .field("matrix", &self.matrix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for ReedSolomon {
+    fn eq(&self, other: &ReedSolomon) -> bool {
+        self.data_shard_count == other.data_shard_count
+            && self.parity_shard_count == other.parity_shard_count
+            && self.systematic == other.systematic
+            && self.gf == other.gf
+            && self.matrix == other.matrix
+    }
 }
 
+impl Eq for ReedSolomon {}
+
 impl ReedSolomon {
+    /// Hash returned by `shard_hashes` for a missing (empty) shard.
+    pub const MISSING_SHARD_HASH: u64 = 0;
+
+    /// Estimated no. of field operations, i.e. data shards cubed, above
+    /// which inverting the data shard matrix may take noticeable time.
+    /// Construction and every decode with missing data shards invert such
+    /// a matrix, e.g. 200 data shards take 8 million operations.
+    pub const EXPENSIVE_INVERT_OPS: u64 = 1 << 22;
+
     /// Create a matrix used for encoding.
     /// Since top square of the matrix is guaranteed to be an identity
     /// matrix, the data shards will remain unchanged after encoding.
@@ -27,17 +161,17 @@ impl ReedSolomon {
     /// * `gf` - Galois Field where all the arithmetic will take place
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::ReedSolomon;
     /// use crate::galois::GaloisField;
     ///
     /// let gf = GaloisField::new();
-    /// let matrix = ReedSolomon::build_matrix(4, 6, gf);
+    /// let matrix = ReedSolomon::build_matrix(4, 6, &gf);
     /// ```
     pub(crate) fn build_matrix(
         data_shards: usize,
         total_shards: usize,
-        gf: GaloisField,
+        gf: &GaloisField,
     ) -> Result<Matrix, Error> {
         // Start with a Vandermonde matrix but this matrix doesn't have the property
         // that the data shards are unchanged after encoding.
@@ -53,6 +187,9 @@ impl ReedSolomon {
     }
 
     /// Create a new Reed Solomon Erasure Coding to be used to encode data.
+    /// Every configuration with at least 1 data shard, at least 1 parity
+    /// shard and at most 256 total shards is supported, including the
+    /// extremes 255 + 1 and 1 + 255.
     /// # Arguments
     ///
     /// * `data_shards` - No. of Data Shards
@@ -65,38 +202,396 @@ impl ReedSolomon {
     /// let rs = ReedSolomon::new(4, 2);
     /// ```
     pub fn new(data_shards: usize, parity_shards: usize) -> Result<ReedSolomon, Error> {
+        ReedSolomonBuilder::new(data_shards, parity_shards).build()
+    }
+
+    /// Create a new Reed Solomon Erasure Coding like `new`, but fails with
+    /// `Error::ConfigTooExpensive` if the estimated no. of operations to
+    /// invert the data shard matrix, i.e. data shards cubed, is above the
+    /// given budget, so interactive callers can avoid a slow construction.
+    /// `EXPENSIVE_INVERT_OPS` is a reasonable budget.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    /// * `max_ops` - Max estimated no. of operations
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new_checked(4, 2, ReedSolomon::EXPENSIVE_INVERT_OPS);
+    /// ```
+    pub fn new_checked(
+        data_shards: usize,
+        parity_shards: usize,
+        max_ops: u64,
+    ) -> Result<ReedSolomon, Error> {
+        let ops = (data_shards as u64).saturating_pow(3);
+        if ops > max_ops {
+            return Err(Error::ConfigTooExpensive(ops, max_ops));
+        }
+
+        Self::new(data_shards, parity_shards)
+    }
+
+    /// Create a new Reed Solomon Erasure Coding whose parity shards are
+    /// generated by the given rows instead of the Vandermonde based ones.
+    /// The encoding matrix is the identity matrix stacked on the parity
+    /// rows, and it must be MDS so that any data shard count shards can
+    /// decode. Checking it tries every combination of rows, so codes for
+    /// which it would take more than `matrix::MAX_MDS_CHECK_OPS`
+    /// operations, e.g. 32 + 32, fail with `Error::ConfigTooExpensive`.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_rows` - One row of data shard count coefficients per parity shard
+    /// * `gf` - Galois Field where all the arithmetic will take place
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::with_custom_parity(2, vec![vec![1, 1], vec![1, 2]], GaloisField::new());
+    /// ```
+    pub fn with_custom_parity(
+        data_shards: usize,
+        parity_rows: Vec<Vec<u8>>,
+        gf: GaloisField,
+    ) -> Result<ReedSolomon, Error> {
+        let parity_shards = parity_rows.len();
+        Self::check_shard_counts(data_shards, parity_shards)?;
+        if parity_rows.iter().any(|row| row.len() != data_shards) {
+            return Err(Error::InconsistentRowLengths);
+        }
+
+        let mut data = Matrix::new_identity(data_shards).into_data();
+        data.extend(parity_rows);
+        let matrix = Matrix::new_from_data(data);
+        if !matrix.is_mds(&gf)? {
+            return Err(Error::NotMds);
+        }
+
+        Ok(Self::from_matrix(
+            data_shards,
+            parity_shards,
+            matrix,
+            gf,
+            true,
+        ))
+    }
+
+    /// Create a new Reed Solomon Erasure Coding with the given encoding
+    /// matrix, e.g. the one of another implementation to read its shards.
+    /// The matrix has one row of data shard count coefficients per shard
+    /// and its top rows must be the identity matrix. The rows below are
+    /// the parity rows of `with_custom_parity`, and are checked the same
+    /// way.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    /// * `matrix` - Encoding matrix with one row per shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::matrix::Matrix;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let matrix = Matrix::from_rows(&[&[1, 0], &[0, 1], &[1, 1], &[1, 2]]).unwrap();
+    /// let rs = ReedSolomon::with_matrix(2, 2, matrix).unwrap();
+    /// ```
+    pub fn with_matrix(
+        data_shards: usize,
+        parity_shards: usize,
+        matrix: Matrix,
+    ) -> Result<ReedSolomon, Error> {
+        Self::check_shard_counts(data_shards, parity_shards)?;
+        if matrix.data.len() != data_shards + parity_shards {
+            return Err(Error::WrongNoOfShards);
+        }
+        let mut rows = matrix.into_data();
+        let parity_rows = rows.split_off(data_shards);
+        if rows != Matrix::new_identity(data_shards).into_data() {
+            return Err(Error::NotSystematic);
+        }
+
+        Self::with_custom_parity(data_shards, parity_rows, GaloisField::new())
+    }
+
+    /// Check the no. of data and parity shards a code is created with.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::ReedSolomon;
+    ///
+    /// ReedSolomon::check_shard_counts(4, 2);
+    /// ```
+    pub(crate) fn check_shard_counts(
+        data_shards: usize,
+        parity_shards: usize,
+    ) -> Result<(), Error> {
         if data_shards == 0 {
             return Err(Error::ZeroDataShards);
         }
         if parity_shards == 0 {
             return Err(Error::ZeroParityShards);
         }
-        // More than 256 will lead to duplicate rows in the Vandermonde matrix,
+        let total_shards = match data_shards.checked_add(parity_shards) {
+            Some(x) => x,
+            None => return Err(Error::ShardsOverflow),
+        };
+        // More than the field size will lead to duplicate rows in the Vandermonde matrix,
         // which would then lead to duplicate rows in the built matrix.
         // Any subset of the rows containing the duplicate rows would
         // be singular and thus non-invertible.
-        if data_shards + parity_shards > 256 {
-            return Err(Error::ShardsOverflow);
+        if total_shards > FIELD_SIZE {
+            return Err(Error::TooManyShardsForField(total_shards, FIELD_SIZE));
         }
 
-        let gf = GaloisField::new();
-        let total_shards = data_shards + parity_shards;
-
-        let matrix = Self::build_matrix(data_shards, total_shards, gf)?;
+        Ok(())
+    }
 
+    /// Create a new Reed Solomon Erasure Coding from an already built
+    /// encoding matrix. The parity matrix is taken from the rows below
+    /// the data rows of the encoding matrix.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    /// * `matrix` - Encoding matrix with one row per shard
+    /// * `gf` - Galois Field where all the arithmetic will take place
+    /// * `systematic` - Whether the data shards remain unchanged after encoding
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::ReedSolomon;
+    /// use crate::galois::GaloisField;
+    ///
+    /// let gf = GaloisField::new();
+    /// let matrix = ReedSolomon::build_matrix(4, 6, &gf).unwrap();
+    /// let rs = ReedSolomon::from_matrix(4, 2, matrix, gf, true);
+    /// ```
+    pub(crate) fn from_matrix(
+        data_shards: usize,
+        parity_shards: usize,
+        matrix: Matrix,
+        gf: GaloisField,
+        systematic: bool,
+    ) -> ReedSolomon {
         let mut parity = Matrix::new(parity_shards, data_shards);
         for i in 0..parity_shards {
             parity.data[i] = matrix.data[data_shards + i].clone();
         }
 
-        Ok(ReedSolomon {
+        ReedSolomon {
             data_shard_count: data_shards,
             parity_shard_count: parity_shards,
-            total_shard_count: total_shards,
+            total_shard_count: data_shards + parity_shards,
             parity,
             gf,
             matrix,
-        })
+            systematic,
+            kernel: Arc::new(TableKernel::new()),
+            decode_cache: Arc::new(Mutex::new(DecodeCache::new(DEFAULT_DECODE_CACHE_SIZE))),
+            evaluation_points: OnceLock::new(),
+        }
+    }
+
+    /// Returns the no. of data shards of the code.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(4, rs.data_shard_count());
+    /// ```
+    pub fn data_shard_count(&self) -> usize {
+        self.data_shard_count
+    }
+
+    /// Returns the no. of parity shards of the code.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(2, rs.parity_shard_count());
+    /// ```
+    pub fn parity_shard_count(&self) -> usize {
+        self.parity_shard_count
+    }
+
+    /// Returns the total no. of shards of the code, i.e. the no. of data
+    /// shards plus the no. of parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(6, rs.total_shard_count());
+    /// ```
+    pub fn total_shard_count(&self) -> usize {
+        self.total_shard_count
+    }
+
+    /// Returns the implementation the code multiplies the shards with,
+    /// i.e. the kind of the kernel it was built with, which is useful for
+    /// logging why encoding is slow on some machine.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::Kernel;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert_eq!(Kernel::Scalar, rs.active_kernel());
+    /// ```
+    pub fn active_kernel(&self) -> Kernel {
+        self.kernel.kind()
+    }
+
+    /// Returns all the shard counts of the code at once.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// let geometry = rs.geometry();
+    /// assert_eq!(6, geometry.total_shards);
+    /// ```
+    pub fn geometry(&self) -> Geometry {
+        Geometry {
+            data_shards: self.data_shard_count,
+            parity_shards: self.parity_shard_count,
+            total_shards: self.total_shard_count,
+        }
+    }
+
+    /// Check whether the data shards of this code and the given one are
+    /// interchangeable, e.g. to keep one set of data shards with parity
+    /// shards of both codes. This is true iff both codes have the same no.
+    /// of data shards, use the same field and map the data to the data
+    /// shards the same way, whatever their no. of parity shards.
+    /// # Arguments
+    ///
+    /// * `other` - Code to be compared with.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// assert!(rs.compatible_with(&ReedSolomon::new(4, 3).unwrap()));
+    /// ```
+    pub fn compatible_with(&self, other: &ReedSolomon) -> bool {
+        self.data_shard_count == other.data_shard_count
+            && self.gf == other.gf
+            && self.matrix.data[..self.data_shard_count]
+                == other.matrix.data[..other.data_shard_count]
+    }
+
+    /// Returns the k x n generator matrix of the code in the systematic
+    /// form [I | P] used by coding theory text books, where k is the no.
+    /// of data shards and n is the total no. of shards. The internal
+    /// encoding matrix is the n x k matrix [I; P] which maps data shards
+    /// to all shards, so the generator matrix is its transpose.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// let generator = rs.generator_matrix();
+    /// ```
+    pub fn generator_matrix(&self) -> Matrix {
+        let mut generator = Matrix::new(self.data_shard_count, self.total_shard_count);
+        for (r, row) in self.matrix.data.iter().enumerate() {
+            for (c, &elem) in row.iter().enumerate() {
+                generator.data[c][r] = elem;
+            }
+        }
+
+        generator
+    }
+
+    /// Returns the p x n parity-check matrix H of the code, where p is the
+    /// no. of parity shards and n is the total no. of shards, such that H
+    /// times any valid codeword is zero. A codeword is the column of bytes
+    /// at the same position of every shard, and H times a received one is
+    /// its syndrome, which is non zero if it was corrupted.
+    /// For the systematic form [I | P] of the generator matrix, H is
+    /// [P^T | I], i.e. the parity matrix followed by the identity. It
+    /// fails only if the encoding matrix of a non-systematic code can't be
+    /// brought into the systematic form.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// let parity_check = rs.parity_check_matrix().unwrap();
+    /// ```
+    pub fn parity_check_matrix(&self) -> Result<Matrix, Error> {
+        let parity = if self.systematic {
+            self.parity
+                .new_sub_matrix(0, self.parity_shard_count, 0, self.data_shard_count)
+        } else {
+            let top =
+                self.matrix
+                    .new_sub_matrix(0, self.data_shard_count, 0, self.data_shard_count);
+            let top_inv = top.invert(&self.gf)?;
+            self.parity.mul(top_inv, &self.gf)?
+        };
+
+        let mut parity_check = Matrix::new(self.parity_shard_count, self.total_shard_count);
+        for (r, row) in parity.data.iter().enumerate() {
+            parity_check.data[r][..self.data_shard_count].copy_from_slice(row);
+            parity_check.data[r][self.data_shard_count + r] = 1;
+        }
+
+        Ok(parity_check)
+    }
+
+    /// Returns a text snapshot of the code which can be pasted into a bug
+    /// report. It contains the shard counts, the field polynomial, the
+    /// encoding matrix and the parity matrix with every element in hex.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// println!("{}", rs.debug_dump());
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let mut dump = format!(
+            "data_shards: {}\nparity_shards: {}\nsystematic: {}\npolynomial: 0x{:x}\n",
+            self.data_shard_count,
+            self.parity_shard_count,
+            self.systematic,
+            FIELD_SIZE | galois::IRREDUCIBLE_POLYNOMIAL,
+        );
+        for (name, matrix) in [("matrix", &self.matrix), ("parity", &self.parity)] {
+            dump.push_str(name);
+            dump.push_str(":\n");
+            for row in matrix.data.iter() {
+                let elems: Vec<String> = row.iter().map(|elem| format!("{:02x}", elem)).collect();
+                dump.push_str(&elems.join(" "));
+                dump.push('\n');
+            }
+        }
+
+        dump
     }
 
     /// Check the consistency of shards passed to other methods.
@@ -105,24 +600,24 @@ impl ReedSolomon {
     /// * `shards` - All shards including data and parity shards.
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use reed_solomon::ReedSolomon;
     ///
     /// let rs = ReedSolomon::new(2, 2);
     /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![200, 201, 203], vec![100, 101, 102]];
     /// rs.check_shard_sizes(shards);
     /// ```
-    pub(crate) fn check_shard_sizes(&self, shards: &Vec<Vec<u8>>) -> Result<(), Error> {
+    pub(crate) fn check_shard_sizes<T: AsRef<[u8]>>(&self, shards: &[T]) -> Result<(), Error> {
         if shards.len() != self.total_shard_count {
             return Err(Error::WrongNoOfShards);
         }
 
-        let shard_elem_len = shards[0].len();
+        let shard_elem_len = shards[0].as_ref().len();
         if shard_elem_len == 0 {
             return Err(Error::EmptyShards);
         }
         for elem in shards.iter() {
-            if (*elem).len() != shard_elem_len {
+            if elem.as_ref().len() != shard_elem_len {
                 return Err(Error::InconsistentShards);
             }
         }
@@ -132,6 +627,8 @@ impl ReedSolomon {
 
     /// Encodes checksum shards for a set of data shards.
     /// Returns all the shards including all data and parity shards.
+    /// For a non-systematic code every shard, data shards included, is
+    /// overwritten by a combination of the data shards.
     /// # Arguments
     ///
     /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
@@ -140,346 +637,5191 @@ impl ReedSolomon {
     /// ```
     /// use reed_solomon::ReedSolomon;
     ///
-    /// let rs = ReedSolomon::new(2, 2);
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
     /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![200, 201, 203], vec![100, 101, 102]];
     /// let encoded_shards = rs.encode(shards);
     /// ```
     pub fn encode(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
-        self.check_shard_sizes(&shards)?;
-
-        let mut inputs = shards[..self.data_shard_count].to_vec();
-        let mut outputs = shards[self.data_shard_count..].to_vec();
+        let mut shards = shards;
+        self.encode_in_place(&mut shards)?;
 
-        self.encode_shards(&self.parity, &inputs, &mut outputs);
+        Ok(shards)
+    }
 
-        inputs.extend(outputs);
+    /// Encodes checksum shards for a set of data shards like `encode`, but
+    /// overwrites the parity shards in place, so that the shards are not
+    /// reallocated. A non-systematic code still copies the data shards,
+    /// since every shard, data shards included, is overwritten.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+    /// rs.encode_in_place(&mut shards).unwrap();
+    /// ```
+    pub fn encode_in_place<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        shards: &mut [T],
+    ) -> Result<(), Error> {
+        self.check_shard_sizes(shards)?;
+        self.encode_stripe(shards);
 
-        Ok(inputs)
+        Ok(())
     }
 
-    /// Encodes checksum shards for a given input (data shards) and modifies the output.
+    /// Encodes checksum shards for the given data shards into the given
+    /// parity buffers, e.g. mmap regions or network buffers, without
+    /// building a `Vec<Vec<u8>>`. Only a systematic code can be encoded
+    /// this way, since a non-systematic one overwrites the data shards.
     /// # Arguments
     ///
-    /// * `inputs` - Data shards.
-    /// * `outputs` - Parity shards (to be overwritten).
+    /// * `data` - Data shards, all of the same length.
+    /// * `parity` - Parity shards (to be overwritten), of the same length as the data shards.
     ///
     /// # Example
     /// ```
     /// use reed_solomon::ReedSolomon;
     ///
-    /// let rs = ReedSolomon::new(2, 2);
-    /// let inputs = vec![vec![0, 1, 2], vec![3, 4, 5]];
-    /// let mut outputs = vec![vec![200, 201, 203], vec![100, 101, 102]];
-    /// rs.encode_shards(&inputs, &mut outputs);
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data: [&[u8]; 2] = [&[0, 1, 2], &[3, 4, 5]];
+    /// let mut parity = [[0u8; 3]; 2];
+    /// let [first, second] = &mut parity;
+    /// rs.encode_sep(&data, &mut [first, second]).unwrap();
     /// ```
-    pub(crate) fn encode_shards(
-        &self,
-        parity: &Matrix,
-        inputs: &Vec<Vec<u8>>,
-        outputs: &mut Vec<Vec<u8>>,
-    ) {
-        for inp in 0..self.data_shard_count {
-            for out in 0..self.parity_shard_count {
-                let parity_byte = (*parity).data[out][inp];
-                if inp == 0 {
-                    for (i_byte, input) in inputs[inp].iter().enumerate() {
-                        outputs[out][i_byte] = self.gf.mul(parity_byte, *input);
-                    }
-                } else {
-                    let mut val: u8;
-                    for (i_byte, input) in inputs[inp].iter().enumerate() {
-                        val = self.gf.mul(parity_byte, *input);
-                        outputs[out][i_byte] = GaloisField::add(outputs[out][i_byte], val);
-                    }
-                }
-            }
-        }
+    pub fn encode_sep(&self, data: &[&[u8]], parity: &mut [&mut [u8]]) -> Result<(), Error> {
+        self.encode_into(data, parity)
     }
 
-    /// Check the no. and consistency of shards passed to decode methods.
+    /// Encodes checksum shards for the given data shards into the given
+    /// pre-allocated parity buffers, like `encode_sep` but for any type of
+    /// buffer, e.g. `&mut [Vec<u8>]`. Nothing is allocated while encoding
+    /// with the default kernel, so it can be used in latency sensitive
+    /// paths. Only a systematic code can be encoded this way.
     /// # Arguments
     ///
-    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `data` - Data shards, all of the same length.
+    /// * `parity` - Parity shards (to be overwritten), of the same length as the data shards.
     ///
     /// # Example
     /// ```
     /// use reed_solomon::ReedSolomon;
     ///
-    /// let rs = ReedSolomon::new(2, 2);
-    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
-    /// rs.check_shard_sizes_for_decode(shards);
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    /// let mut parity = vec![vec![0; 3]; 2];
+    /// rs.encode_into(&data, &mut parity).unwrap();
+    /// assert_eq!(vec![vec![6, 11, 12], vec![5, 14, 11]], parity);
     /// ```
-    pub(crate) fn check_shard_sizes_for_decode(
+    pub fn encode_into<T: AsRef<[u8]>, U: AsMut<[u8]>>(
         &self,
-        shards: &Vec<Vec<u8>>,
-    ) -> Result<(usize, usize), Error> {
-        if shards.len() < self.total_shard_count {
-            return Err(Error::TooFewShards);
-        }
-        if shards.len() > self.total_shard_count {
-            return Err(Error::TooManyShards);
+        data: &[T],
+        parity: &mut [U],
+    ) -> Result<(), Error> {
+        if !self.systematic {
+            return Err(Error::NotSystematic);
         }
-
-        let mut shard_elem_len = 0;
-        let mut present: usize = 0;
-        for elem in shards.iter() {
-            if (*elem).len() != 0 {
-                present += 1;
-                shard_elem_len = (*elem).len();
-            }
+        if data.len() != self.data_shard_count || parity.len() != self.parity_shard_count {
+            return Err(Error::WrongNoOfShards);
         }
-        let mut same_size: usize = 0;
-        for elem in shards.iter() {
-            if (*elem).len() == shard_elem_len {
-                same_size += 1;
-            }
+        let shard_elem_len = data[0].as_ref().len();
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
         }
-        if present != same_size {
+        if data
+            .iter()
+            .any(|shard| shard.as_ref().len() != shard_elem_len)
+            || parity
+                .iter_mut()
+                .any(|shard| shard.as_mut().len() != shard_elem_len)
+        {
             return Err(Error::InconsistentShards);
         }
-        if present < self.data_shard_count {
-            return Err(Error::TooFewShards);
-        }
 
-        Ok((present, shard_elem_len))
+        self.encode_shards(&self.parity, data, parity);
+
+        Ok(())
     }
 
-    /// Takes shards as input and recover any data or parity shards that is missing.
-    /// Returns all the shards including all data and parity shards.
+    /// Encodes checksum shards for the data shards yielded by the given
+    /// iterator, e.g. the pieces of a ring buffer or scatter list, without
+    /// collecting them first. Every data shard is read once, as it is
+    /// yielded. Only a systematic code can be encoded this way.
+    /// Returns the parity shards.
     /// # Arguments
     ///
-    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `data` - Data shards, all of the same length.
     ///
     /// # Example
     /// ```
     /// use reed_solomon::ReedSolomon;
     ///
-    /// let rs = ReedSolomon::new(2, 2);
-    /// let inputs = vec![vec![0, 1, 2], vec![3, 4, 5]];
-    /// let mut outputs = vec![vec![200, 201, 203], vec![100, 101, 102]];
-    /// rs.encode_shards(&inputs, &mut outputs);
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let ring = [0, 1, 2, 3, 4, 5];
+    /// let parity = rs.encode_iter(ring.chunks(3)).unwrap();
+    /// assert_eq!(vec![vec![6, 11, 12], vec![5, 14, 11]], parity);
     /// ```
-    pub fn decode(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
-        let (present, shard_elem_len) = self.check_shard_sizes_for_decode(&shards)?;
+    pub fn encode_iter<'a, I: IntoIterator<Item = &'a [u8]>>(
+        &self,
+        data: I,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        if !self.systematic {
+            return Err(Error::NotSystematic);
+        }
+
+        let mut parity: Vec<Vec<u8>> = vec![];
+        let mut count = 0;
+        for shard in data {
+            if count == self.data_shard_count {
+                return Err(Error::WrongNoOfShards);
+            }
+            if count == 0 {
+                if shard.is_empty() {
+                    return Err(Error::EmptyShards);
+                }
+                parity = vec![vec![0; shard.len()]; self.parity_shard_count];
+            } else if shard.len() != parity[0].len() {
+                return Err(Error::InconsistentShards);
+            }
+
+            for (row, output) in self.parity.data.iter().zip(parity.iter_mut()) {
+                if count == 0 {
+                    self.kernel.mul_slice(row[count], shard, output);
+                } else {
+                    self.kernel.mul_slice_add(row[count], shard, output);
+                }
+            }
+            count += 1;
+        }
+        if count != self.data_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+
+        Ok(parity)
+    }
+
+    /// Encodes checksum shards for many stripes in one call, like calling
+    /// `encode_in_place` on each of them. Every stripe is checked before
+    /// any of them is encoded, so on error no stripe is modified. With the
+    /// `rayon` feature the stripes are encoded in parallel.
+    /// # Arguments
+    ///
+    /// * `stripes` - Stripes of all shards including data and parity shards. Parity shards will be overwritten.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut stripes = vec![
+    ///     vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]],
+    ///     vec![vec![6, 7], vec![8, 9], vec![0; 2], vec![0; 2]],
+    /// ];
+    /// rs.encode_batch(&mut stripes).unwrap();
+    /// assert_eq!(vec![5, 14, 11], stripes[0][3]);
+    /// ```
+    pub fn encode_batch(&self, stripes: &mut [Stripe]) -> Result<(), Error> {
+        for stripe in stripes.iter() {
+            self.check_shard_sizes(stripe)?;
+        }
+
+        self.encode_batch_par(stripes);
+
+        Ok(())
+    }
+
+    /// Encodes the already checked stripes in parallel, one stripe per
+    /// task, since the stripes don't depend on each other.
+    /// # Arguments
+    ///
+    /// * `stripes` - Stripes of all shards including data and parity shards.
+    #[cfg(feature = "rayon")]
+    fn encode_batch_par(&self, stripes: &mut [Stripe]) {
+        use rayon::prelude::*;
+
+        stripes
+            .par_iter_mut()
+            .for_each(|stripe| self.encode_stripe(stripe));
+    }
+
+    /// Without the `rayon` feature there is nothing to run in parallel,
+    /// so the stripes are encoded one after another.
+    #[cfg(not(feature = "rayon"))]
+    fn encode_batch_par(&self, stripes: &mut [Stripe]) {
+        for stripe in stripes.iter_mut() {
+            self.encode_stripe(stripe);
+        }
+    }
+
+    /// Encodes the already checked shards of one stripe in place.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    fn encode_stripe<T: AsRef<[u8]> + AsMut<[u8]>>(&self, shards: &mut [T]) {
+        if !self.systematic {
+            // Every shard is a combination of all data shards, so all of
+            // them are overwritten.
+            let inputs: Vec<Vec<u8>> = shards[..self.data_shard_count]
+                .iter()
+                .map(|shard| shard.as_ref().to_vec())
+                .collect();
+            self.encode_shards(&self.matrix, &inputs, shards);
+
+            return;
+        }
+
+        let (inputs, outputs) = shards.split_at_mut(self.data_shard_count);
+        self.encode_shards(&self.parity, inputs, outputs);
+    }
+
+    /// Check whether the shards are still a valid set of shards i.e.
+    /// whether the parity shards match the data shards, without decoding.
+    /// The parity is computed into a scratch buffer one shard at a time.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// assert!(rs.verify(&shards).unwrap());
+    /// ```
+    pub fn verify<T: AsRef<[u8]>>(&self, shards: &[T]) -> Result<bool, Error> {
+        Ok(self
+            .mismatched_parity(shards, &*self.verify_rows()?)?
+            .is_empty())
+    }
+
+    /// Check whether the shards are still a valid set of shards like
+    /// `verify`, and report which parity shards don't match. If they don't
+    /// and there are at least 2 parity shards, every shard is left out in
+    /// turn and recomputed from the others, and the shards whose
+    /// recomputation makes the set valid are reported as suspects. This
+    /// localizes a single corrupted shard; if more than one shard is
+    /// corrupted, usually no shard is a suspect.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// shards[1][0] ^= 1;
+    /// let report = rs.verify_with_report(&shards).unwrap();
+    /// assert_eq!(Some(vec![1]), report.suspects);
+    /// ```
+    pub fn verify_with_report<T: AsRef<[u8]>>(&self, shards: &[T]) -> Result<VerifyReport, Error> {
+        let rows = self.verify_rows()?;
+        let mismatched_parity = self.mismatched_parity(shards, &rows)?;
+        if mismatched_parity.is_empty() {
+            return Ok(VerifyReport {
+                mismatched_parity,
+                suspects: Some(vec![]),
+            });
+        }
+        // With a single parity shard, leaving any shard out makes the
+        // rest consistent.
+        if self.parity_shard_count < 2 {
+            return Ok(VerifyReport {
+                mismatched_parity,
+                suspects: None,
+            });
+        }
+
+        let given: Vec<Option<Vec<u8>>> = shards
+            .iter()
+            .map(|shard| Some(shard.as_ref().to_vec()))
+            .collect();
+        let candidates: Vec<usize> = (0..self.total_shard_count).collect();
+        let suspects = self.leave_one_out_suspects(&given, &candidates, &rows)?;
+
+        Ok(VerifyReport {
+            mismatched_parity,
+            suspects: Some(suspects),
+        })
+    }
+
+    /// Corrects up to half the no. of parity shards of silently corrupted
+    /// shards in place, whose positions are unknown unlike the missing
+    /// shards of `decode`. The shards are checked at every byte position,
+    /// and a position which is inconsistent is corrected with the
+    /// Berlekamp-Welch algorithm, so more shards can be corrected as long
+    /// as no position has more errors than that. If any position has
+    /// more, it fails with `Error::TooManyErrors` and leaves the shards
+    /// untouched. It needs the encoding matrix to be built from the
+    /// Vandermonde matrix, i.e. the one of `new` or of a non-systematic
+    /// code, and fails with `Error::UnsupportedMatrix` otherwise.
+    /// Returns the indices of the corrected shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// shards[0][1] = 9;
+    /// assert_eq!(vec![0], rs.correct(&mut shards).unwrap());
+    /// assert_eq!(vec![0, 1, 2], shards[0]);
+    /// ```
+    pub fn correct(&self, shards: &mut [Vec<u8>]) -> Result<Vec<usize>, Error> {
+        self.check_shard_sizes(shards)?;
+        let points = self.evaluation_points()?;
+
+        // Every parity-check row times a consistent position is 0, so
+        // only the positions with a non zero syndrome are corrected.
+        let parity_check = self.parity_check_matrix()?;
+        let shard_elem_len = shards[0].len();
+        let mut inconsistent: Vec<bool> = vec![false; shard_elem_len];
+        let mut syndrome: Vec<u8> = vec![0; shard_elem_len];
+        for row in parity_check.data.iter() {
+            self.combine_row(row, shards, &mut syndrome);
+            for (flag, &x) in inconsistent.iter_mut().zip(syndrome.iter()) {
+                *flag |= x != 0;
+            }
+        }
+
+        // Every position is decoded before any shard is written, so that
+        // the shards are left untouched if one can't be corrected.
+        let mut fixes: Vec<(usize, usize, u8)> = vec![];
+        let mut values: Vec<u8> = vec![0; self.total_shard_count];
+        for c in (0..shard_elem_len).filter(|&c| inconsistent[c]) {
+            for (value, shard) in values.iter_mut().zip(shards.iter()) {
+                *value = shard[c];
+            }
+            let fixed = match welch::welch_decode(&self.gf, points, &values, self.data_shard_count)
+            {
+                Some(x) => x,
+                None => return Err(Error::TooManyErrors),
+            };
+            for (i, &value) in fixed.iter().enumerate() {
+                if value != values[i] {
+                    fixes.push((i, c, value));
+                }
+            }
+        }
+
+        let mut corrected = vec![false; self.total_shard_count];
+        for (i, c, value) in fixes {
+            shards[i][c] = value;
+            corrected[i] = true;
+        }
+
+        Ok((0..self.total_shard_count)
+            .filter(|&i| corrected[i])
+            .collect())
+    }
+
+    /// Returns the points at which the shards are the evaluations of a
+    /// polynomial of degree less than the no. of data shards, i.e. the
+    /// bases of the Vandermonde matrix the encoding matrix is built from.
+    /// Fails with `Error::UnsupportedMatrix` for any other matrix. They
+    /// are only found on the first call, which builds the matrix again.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let points = rs.evaluation_points();
+    /// ```
+    fn evaluation_points(&self) -> Result<&[u8], Error> {
+        if let Some(points) = self.evaluation_points.get() {
+            return points.as_deref().ok_or(Error::UnsupportedMatrix);
+        }
+
+        let points = self.find_evaluation_points()?;
+        self.evaluation_points
+            .get_or_init(|| points)
+            .as_deref()
+            .ok_or(Error::UnsupportedMatrix)
+    }
+
+    /// Returns the bases of the Vandermonde matrix the encoding matrix is
+    /// built from, or None for any other matrix.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let points = rs.find_evaluation_points();
+    /// ```
+    fn find_evaluation_points(&self) -> Result<Option<Vec<u8>>, Error> {
+        let k = self.data_shard_count;
+        let n = self.total_shard_count;
+        // A systematic code maps the data to the evaluations at 0 to k - 1
+        // and a non-systematic code maps it to the coefficients.
+        let (points, matrix): (Vec<u8>, Matrix) = if self.systematic {
+            (
+                (0..n).map(|x| x as u8).collect(),
+                Self::build_matrix(k, n, &self.gf)?,
+            )
+        } else {
+            let points: Vec<u8> = (1..=n).map(|x| x as u8).collect();
+            let mut matrix = Matrix::new(n, k);
+            for (&x, row) in points.iter().zip(matrix.data.iter_mut()) {
+                self.gf.fill_vandermonde_row(x, row);
+            }
+            (points, matrix)
+        };
+        if matrix != self.matrix {
+            return Ok(None);
+        }
+
+        Ok(Some(points))
+    }
+
+    /// Returns the rows computing every parity shard from the first data
+    /// shard count shards, which are the parity rows of a systematic code.
+    /// For a non-systematic code they are the first columns of H, so the
+    /// parity-check matrix is computed once and passed to
+    /// `mismatched_parity` by the callers checking many sets of shards.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let rows = rs.verify_rows();
+    /// ```
+    fn verify_rows(&self) -> Result<Cow<'_, Matrix>, Error> {
+        if self.systematic {
+            return Ok(Cow::Borrowed(&self.parity));
+        }
+
+        Ok(Cow::Owned(self.parity_check_matrix()?.new_sub_matrix(
+            0,
+            self.parity_shard_count,
+            0,
+            self.data_shard_count,
+        )))
+    }
+
+    /// Returns the indices of the parity shards which don't match the data
+    /// shards, counted from the first parity shard.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    /// * `rows` - Rows computing the parity shards, as returned by `verify_rows`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 11, 12], vec![5, 14, 11]];
+    /// let mismatched = rs.mismatched_parity(&shards, &rs.verify_rows().unwrap());
+    /// ```
+    fn mismatched_parity<T: AsRef<[u8]>>(
+        &self,
+        shards: &[T],
+        rows: &Matrix,
+    ) -> Result<Vec<usize>, Error> {
+        self.check_shard_sizes(shards)?;
+
+        // The parity is computed into a scratch buffer one shard at a time.
+        let (inputs, outputs) = shards.split_at(self.data_shard_count);
+        let mut expected: Vec<u8> = vec![0; inputs[0].as_ref().len()];
+        let mut mismatched: Vec<usize> = vec![];
+        for (j, (row, output)) in rows.data.iter().zip(outputs.iter()).enumerate() {
+            self.combine_row(row, inputs, &mut expected);
+            if expected[..] != *output.as_ref() {
+                mismatched.push(j);
+            }
+        }
+
+        Ok(mismatched)
+    }
+
+    /// Leaves every candidate shard out of the given shards in turn,
+    /// recovers it and the missing shards from the others, and returns the
+    /// candidates whose recovery makes the parity shards match, i.e. the
+    /// shards whose corruption alone explains a mismatch.
+    /// # Arguments
+    ///
+    /// * `given` - All shards including data and parity shards. Missing shards are `None`.
+    /// * `candidates` - Indices of the present shards which may be corrupted.
+    /// * `rows` - Rows computing the parity shards, as returned by `verify_rows`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let given = vec![Some(vec![0, 1, 9]), Some(vec![3, 4, 5]), Some(vec![6, 11, 12]), Some(vec![5, 14, 11])];
+    /// let suspects = rs.leave_one_out_suspects(&given, &[0, 1, 2, 3], &rs.verify_rows().unwrap());
+    /// ```
+    fn leave_one_out_suspects(
+        &self,
+        given: &[Option<Vec<u8>>],
+        candidates: &[usize],
+        rows: &Matrix,
+    ) -> Result<Vec<usize>, Error> {
+        let mut suspects: Vec<usize> = vec![];
+        for &i in candidates.iter() {
+            let mut repaired = given.to_vec();
+            repaired[i] = None;
+            self.reconstruct(&mut repaired)?;
+            let repaired: Vec<Vec<u8>> = repaired.into_iter().flatten().collect();
+            if self.mismatched_parity(&repaired, rows)?.is_empty() {
+                suspects.push(i);
+            }
+        }
+
+        Ok(suspects)
+    }
+
+    /// Computes the last parity shard of this code for a complete set of
+    /// shards encoded with one parity shard less, e.g. to add a 3rd parity
+    /// shard to a 4+2 set with a 4+3 code. The parity rows of a code only
+    /// depend on the no. of data shards and their index, so the given
+    /// shards followed by the returned one are a valid set of this code.
+    /// Only the data shards are read.
+    /// Returns the new parity shard.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards of the code with one parity shard less.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 1).unwrap();
+    /// let old = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3]]).unwrap();
+    /// let more = ReedSolomon::new(2, 2).unwrap();
+    /// let new_parity = more.add_parity_shard(&old).unwrap();
+    /// ```
+    pub fn add_parity_shard(&self, shards: &[Vec<u8>]) -> Result<Vec<u8>, Error> {
+        if shards.len() + 1 != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let data = &shards[..self.data_shard_count];
+        let shard_elem_len = data[0].len();
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if data.iter().any(|shard| shard.len() != shard_elem_len) {
+            return Err(Error::InconsistentShards);
+        }
+
+        let parity_check = self.parity_check_matrix()?;
+        let row = &parity_check.data[self.parity_shard_count - 1][..self.data_shard_count];
+        let mut output: Vec<u8> = vec![0; shard_elem_len];
+        self.combine_row(row, data, &mut output);
+
+        Ok(output)
+    }
+
+    /// Computes a single parity shard of the given data shards, e.g. to
+    /// replace one lost parity shard without computing all of them.
+    /// Returns the parity shard of the given index.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards, all of the same length.
+    /// * `parity_index` - Index of the parity shard, counted from the first parity shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data: [&[u8]; 2] = [&[0, 1, 2], &[3, 4, 5]];
+    /// assert_eq!(vec![5, 14, 11], rs.encode_parity_shard(&data, 1).unwrap());
+    /// ```
+    pub fn encode_parity_shard<T: AsRef<[u8]>>(
+        &self,
+        data: &[T],
+        parity_index: usize,
+    ) -> Result<Vec<u8>, Error> {
+        if data.len() != self.data_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        if parity_index >= self.parity_shard_count {
+            return Err(Error::InvalidShardIndex(parity_index));
+        }
+        let shard_elem_len = data[0].as_ref().len();
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if data
+            .iter()
+            .any(|shard| shard.as_ref().len() != shard_elem_len)
+        {
+            return Err(Error::InconsistentShards);
+        }
+
+        // The row of the encoding matrix maps the data to the shard for a
+        // non-systematic code as well.
+        let row = &self.matrix.data[self.data_shard_count + parity_index];
+        let mut output: Vec<u8> = vec![0; shard_elem_len];
+        self.combine_row(row, data, &mut output);
+
+        Ok(output)
+    }
+
+    /// Updates the parity shards after one data shard changed, without
+    /// reading the other data shards. Since the code is linear, every
+    /// parity shard changes by the difference of the data shard times its
+    /// coefficient. Only supported by systematic codes, as every shard of
+    /// a non-systematic code depends on every data shard.
+    /// # Arguments
+    ///
+    /// * `old_data` - Data shard before the change.
+    /// * `new_data` - Data shard after the change, of the same length.
+    /// * `shard_index` - Index of the data shard.
+    /// * `parity` - Parity shards to be updated in place.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut parity = vec![vec![6, 11, 12], vec![5, 14, 11]];
+    /// rs.update(&[3, 4, 5], &[3, 4, 5], 1, &mut parity).unwrap();
+    /// assert_eq!(vec![vec![6, 11, 12], vec![5, 14, 11]], parity);
+    /// ```
+    pub fn update<U: AsMut<[u8]>>(
+        &self,
+        old_data: &[u8],
+        new_data: &[u8],
+        shard_index: usize,
+        parity: &mut [U],
+    ) -> Result<(), Error> {
+        if !self.systematic {
+            return Err(Error::NotSystematic);
+        }
+        if shard_index >= self.data_shard_count {
+            return Err(Error::InvalidShardIndex(shard_index));
+        }
+        if parity.len() != self.parity_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let shard_elem_len = old_data.len();
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if new_data.len() != shard_elem_len
+            || parity
+                .iter_mut()
+                .any(|shard| shard.as_mut().len() != shard_elem_len)
+        {
+            return Err(Error::InconsistentShards);
+        }
+
+        let delta: Vec<u8> = old_data
+            .iter()
+            .zip(new_data.iter())
+            .map(|(&old, &new)| GaloisField::add(old, new))
+            .collect();
+        for (row, shard) in self.parity.data.iter().zip(parity.iter_mut()) {
+            self.kernel
+                .mul_slice_add(row[shard_index], &delta, shard.as_mut());
+        }
+
+        Ok(())
+    }
+
+    /// Encodes checksum shards like `encode`, but turns any panic during
+    /// the encode into `Error::Internal`, so that a bad input can't take
+    /// down a worker thread of a server. This is a defensive wrapper, not
+    /// a substitute for handling the errors returned by `encode`.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+    /// let encoded_shards = rs.encode_catch(shards);
+    /// ```
+    pub fn encode_catch(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        Self::catch_panic(|| self.encode(shards))
+    }
+
+    /// Runs the given function and turns a panic in it into
+    /// `Error::Internal` holding the panic message.
+    fn catch_panic<T>(f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                    message.to_string()
+                } else if let Some(message) = payload.downcast_ref::<String>() {
+                    message.clone()
+                } else {
+                    String::from("unknown panic")
+                };
+                Err(Error::Internal(message))
+            }
+        }
+    }
+
+    /// Encodes checksum shards for data shards pulled from the given
+    /// iterator. Exactly data shard count chunks are taken from it, and
+    /// they must all have the same length.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `chunks` - Iterator yielding the data shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let chunks = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    /// let encoded_shards = rs.encode_from_chunks(chunks.into_iter());
+    /// ```
+    pub fn encode_from_chunks<I: Iterator<Item = Vec<u8>>>(
+        &self,
+        chunks: I,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards: Vec<Vec<u8>> = chunks.take(self.data_shard_count).collect();
+        if shards.len() < self.data_shard_count {
+            return Err(Error::TooFewShards);
+        }
+
+        let shard_elem_len = shards[0].len();
+        shards.resize(self.total_shard_count, vec![0; shard_elem_len]);
+
+        self.encode(shards)
+    }
+
+    /// Encodes checksum shards for the data shards and returns all the
+    /// shards in the order of the given layout, e.g. to match the order of
+    /// the shards on disk. Every data shard and every parity shard must
+    /// appear exactly once in the layout.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards, all of the same length.
+    /// * `layout` - Role of the shard at every position.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::{ReedSolomon, ShardRole};
+    ///
+    /// let rs = ReedSolomon::new(2, 1).unwrap();
+    /// let layout = [ShardRole::Data(0), ShardRole::Parity(0), ShardRole::Data(1)];
+    /// let shards = rs.encode_with_layout(&[vec![0, 1], vec![2, 3]], &layout).unwrap();
+    /// assert_eq!(vec![2, 3], shards[2]);
+    /// ```
+    pub fn encode_with_layout(
+        &self,
+        data: &[Vec<u8>],
+        layout: &[ShardRole],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        if data.len() != self.data_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+
+        if layout.len() != self.total_shard_count {
+            return Err(Error::InvalidLayout);
+        }
+        let mut seen = vec![false; self.total_shard_count];
+        for role in layout.iter() {
+            let index = match *role {
+                ShardRole::Data(i) if i < self.data_shard_count => i,
+                ShardRole::Parity(j) if j < self.parity_shard_count => self.data_shard_count + j,
+                _ => return Err(Error::InvalidLayout),
+            };
+            if seen[index] {
+                return Err(Error::InvalidLayout);
+            }
+            seen[index] = true;
+        }
+
+        let shard_elem_len = data[0].len();
+        let mut shards = data.to_vec();
+        shards.resize(self.total_shard_count, vec![0; shard_elem_len]);
+        let mut shards: Vec<Option<Vec<u8>>> = self.encode(shards)?.into_iter().map(Some).collect();
+
+        Ok(layout
+            .iter()
+            .map(|role| match *role {
+                ShardRole::Data(i) => shards[i].take(),
+                ShardRole::Parity(j) => shards[self.data_shard_count + j].take(),
+            })
+            .map(|shard| shard.unwrap_or_default())
+            .collect())
+    }
+
+    /// Encodes checksum shards like `encode`, but checks the given flag
+    /// before computing every chunk of `PROGRESS_CHUNK_LEN` bytes of every
+    /// output shard and stops with `Error::Cancelled` as soon as it is
+    /// set. This lets a server abort a large encode whose request has
+    /// timed out.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    /// * `should_cancel` - Flag set by another thread to cancel the encode.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::atomic::AtomicBool;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let should_cancel = AtomicBool::new(false);
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0, 0, 0], vec![0, 0, 0]];
+    /// let encoded_shards = rs.encode_cancellable(shards, &should_cancel);
+    /// ```
+    pub fn encode_cancellable(
+        &self,
+        shards: Vec<Vec<u8>>,
+        should_cancel: &AtomicBool,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.encode_in_chunks(shards, &mut |_, _| {}, &|| {
+            should_cancel.load(Ordering::Relaxed)
+        })
+    }
+
+    /// Encodes checksum shards for a given input (data shards) and modifies the output.
+    /// # Arguments
+    ///
+    /// * `inputs` - Data shards.
+    /// * `outputs` - Parity shards (to be overwritten). One per row of parity.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2);
+    /// let inputs = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    /// let mut outputs = vec![vec![200, 201, 203], vec![100, 101, 102]];
+    /// rs.encode_shards(&inputs, &mut outputs);
+    /// ```
+    pub(crate) fn encode_shards<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        parity: &Matrix,
+        inputs: &[T],
+        outputs: &mut [U],
+    ) {
+        for (row, output) in parity.data.iter().zip(outputs.iter_mut()) {
+            self.combine_row(row, inputs, output.as_mut());
+        }
+    }
+
+    /// Computes the linear combination of the given shards with the given
+    /// coefficients i.e. out[i] = XOR of mul(coeffs[j], shards[j][i]) over
+    /// all j. This is the primitive used by both encode and decode.
+    /// # Arguments
+    ///
+    /// * `coeffs` - One coefficient per shard.
+    /// * `shards` - Shards to be combined, all of the same length.
+    /// * `out` - Combination of the shards (to be overwritten), of the same length as the shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    /// let mut out = vec![0; 3];
+    /// rs.apply_row(&[27, 28], &shards, &mut out).unwrap();
+    /// ```
+    pub fn apply_row<T: AsRef<[u8]>>(
+        &self,
+        coeffs: &[u8],
+        shards: &[T],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if coeffs.len() != shards.len() {
+            return Err(Error::WrongNoOfShards);
+        }
+        for shard in shards.iter() {
+            if shard.as_ref().len() != out.len() {
+                return Err(Error::InconsistentShards);
+            }
+        }
+
+        self.combine_row(coeffs, shards, out);
+
+        Ok(())
+    }
+
+    /// Returns the inverse of the rows of the encoding matrix of the given
+    /// shards, which maps those shards back to the data. It is taken from
+    /// the decode cache if the same shards were used to decode before.
+    /// # Arguments
+    ///
+    /// * `rows` - Indices of data shard count shards.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data_decode_matrix = rs.decode_matrix(&[1, 2]);
+    /// ```
+    fn decode_matrix(&self, rows: &[usize]) -> Result<Matrix, Error> {
+        let key = DecodeCache::key(rows);
+        // A poisoned cache is only skipped, decoding doesn't depend on it.
+        if let Ok(mut cache) = self.decode_cache.lock() {
+            if let Some(matrix) = cache.get(&key) {
+                return Ok(matrix);
+            }
+        }
+
+        let data_decode_matrix = self.matrix.submatrix_by_rows(rows)?.invert(&self.gf)?;
+        if let Ok(mut cache) = self.decode_cache.lock() {
+            cache.insert(key, data_decode_matrix.clone());
+        }
+
+        Ok(data_decode_matrix)
+    }
+
+    /// Computes the linear combination of the given shards with the given
+    /// coefficients without checking the no. and length of the shards.
+    /// # Arguments
+    ///
+    /// * `coeffs` - One coefficient per shard.
+    /// * `shards` - Shards to be combined, all of the same length.
+    /// * `out` - Combination of the shards (to be overwritten), of the same length as the shards.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    /// let mut out = vec![0; 3];
+    /// rs.combine_row(&[27, 28], &shards, &mut out);
+    /// ```
+    pub(crate) fn combine_row<T: AsRef<[u8]>>(&self, coeffs: &[u8], shards: &[T], out: &mut [u8]) {
+        let mut terms = coeffs.iter().zip(shards.iter());
+        match terms.next() {
+            Some((&coeff, shard)) => self.kernel.mul_slice(coeff, shard.as_ref(), out),
+            None => out.fill(0),
+        }
+        for (&coeff, shard) in terms {
+            self.kernel.mul_slice_add(coeff, shard.as_ref(), out);
+        }
+    }
+
+    /// Check the no. and consistency of shards passed to decode methods.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2);
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// rs.check_shard_sizes_for_decode(shards);
+    /// ```
+    pub(crate) fn check_shard_sizes_for_decode(
+        &self,
+        shards: &[Vec<u8>],
+    ) -> Result<(usize, usize), Error> {
+        let lens: Vec<Option<usize>> = shards
+            .iter()
+            .map(|shard| (!shard.is_empty()).then_some(shard.len()))
+            .collect();
+        self.check_present_lens(&lens)
+    }
+
+    /// Check the no. and consistency of shards passed to decode methods,
+    /// given the length of every present shard and None for every missing
+    /// one. Returns the no. of present shards and their length.
+    /// # Arguments
+    ///
+    /// * `lens` - Length of every shard, None if it is missing.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2);
+    /// rs.check_present_lens(&[Some(3), None, Some(3), None]);
+    /// ```
+    fn check_present_lens(&self, lens: &[Option<usize>]) -> Result<(usize, usize), Error> {
+        if lens.len() < self.total_shard_count {
+            return Err(Error::TooFewShards);
+        }
+        if lens.len() > self.total_shard_count {
+            return Err(Error::TooManyShards);
+        }
+
+        let mut present_lens = lens.iter().flatten();
+        let shard_elem_len = match present_lens.next() {
+            Some(&len) => len,
+            None => return Err(Error::TooFewShards),
+        };
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if present_lens.any(|&len| len != shard_elem_len) {
+            return Err(Error::InconsistentShards);
+        }
+        let present = lens.iter().flatten().count();
+        if present < self.data_shard_count {
+            return Err(Error::TooFewShards);
+        }
+
+        Ok((present, shard_elem_len))
+    }
+
+    /// Takes shards as input, with `None` for every missing shard, and
+    /// recover every missing data or parity shard in place. This is the
+    /// main way to repair shards: unlike the empty shards taken by
+    /// `decode`, a `None` can't be mistaken for a shard. For a
+    /// non-systematic code, the shards are the ones returned by `encode`.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![Some(vec![0, 1, 2]), None, Some(vec![6, 11, 12]), None];
+    /// rs.reconstruct(&mut shards).unwrap();
+    /// ```
+    pub fn reconstruct<T: AsRef<[u8]> + From<Vec<u8>>>(
+        &self,
+        shards: &mut [Option<T>],
+    ) -> Result<(), Error> {
+        let wanted: Vec<usize> = (0..self.total_shard_count).collect();
+        self.reconstruct_shards(shards, &wanted)
+    }
+
+    /// Takes shards as input, with `None` for every missing shard, and
+    /// recover only the missing data shards in place, skipping the parity
+    /// shards, for readers who only need the data back. For a
+    /// non-systematic code, the data shards are the first data shard
+    /// count shards returned by `encode`.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![Some(vec![0, 1, 2]), None, Some(vec![6, 11, 12]), None];
+    /// rs.reconstruct_data(&mut shards).unwrap();
+    /// assert_eq!(Some(vec![3, 4, 5]), shards[1]);
+    /// assert_eq!(None, shards[3]);
+    /// ```
+    pub fn reconstruct_data<T: AsRef<[u8]> + From<Vec<u8>>>(
+        &self,
+        shards: &mut [Option<T>],
+    ) -> Result<(), Error> {
+        let wanted: Vec<usize> = (0..self.data_shard_count).collect();
+        self.reconstruct_shards(shards, &wanted)
+    }
+
+    /// Takes shards as input, with `None` for every missing shard, and
+    /// recover only the missing shards at the given indices in place, data
+    /// or parity shards alike. Only the rows of the decode matrix for those
+    /// shards are built, and the other missing shards are left missing.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are `None`.
+    /// * `wanted` - Indices of the shards to be recovered.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = vec![Some(vec![0, 1, 2]), None, Some(vec![6, 11, 12]), None];
+    /// rs.reconstruct_shards(&mut shards, &[3]).unwrap();
+    /// assert_eq!(None, shards[1]);
+    /// ```
+    pub fn reconstruct_shards<T: AsRef<[u8]> + From<Vec<u8>>>(
+        &self,
+        shards: &mut [Option<T>],
+        wanted: &[usize],
+    ) -> Result<(), Error> {
+        let lens: Vec<Option<usize>> = shards
+            .iter()
+            .map(|shard| shard.as_ref().map(|x| x.as_ref().len()))
+            .collect();
+        let (_, shard_elem_len) = self.check_present_lens(&lens)?;
+
+        let mut is_wanted = vec![false; self.total_shard_count];
+        for &i in wanted.iter() {
+            if i >= self.total_shard_count {
+                return Err(Error::InvalidShardIndex(i));
+            }
+            is_wanted[i] = true;
+        }
+        let missing: Vec<usize> = (0..self.total_shard_count)
+            .filter(|&i| is_wanted[i] && shards[i].is_none())
+            .collect();
+        if missing.is_empty() {
+            // All of the wanted shards have data so we can return
+            return Ok(());
+        }
+
+        // Pull out the rows of the matrix that correspond
+        // to the given shards and build a square matrix.
+        // This matrix could be used to generate the shards
+        // that we have from the original data.
+        //
+        // Create an array holding just the shards that
+        // correspond to the rows of the submatrix. These
+        // shards will be the input to the decoding process
+        // that re-creates the missing shards.
+        let sub_matrix_rows: Vec<usize> = (0..self.total_shard_count)
+            .filter(|&i| shards[i].is_some())
+            .take(self.data_shard_count)
+            .collect();
+        let sub_shard: Vec<&[u8]> = sub_matrix_rows
+            .iter()
+            .map(|&i| shards[i].as_ref().map_or(&[][..], |x| x.as_ref()))
+            .collect();
+        let matrix_rows =
+            if self.systematic && sub_matrix_rows.iter().copied().eq(0..self.data_shard_count) {
+                // All the data shards are present, so only parity shards are
+                // missing and they can be encoded again without inverting.
+                self.matrix.submatrix_by_rows(&missing)?
+            } else {
+                // Invert the matrix, or take it from the decode cache, so we
+                // can go from the encoded shards back to the original data.
+                // Multiplying the rows of the
+                // encoding matrix for the missing shards by it gives the
+                // rows which create those shards from the given shards
+                // directly, for data and parity shards alike.
+                let data_decode_matrix = self.decode_matrix(&sub_matrix_rows)?;
+                self.matrix
+                    .submatrix_by_rows(&missing)?
+                    .mul(data_decode_matrix, &self.gf)?
+            };
+        let mut outputs: Vec<Vec<u8>> = vec![vec![0; shard_elem_len]; missing.len()];
+        self.encode_shards(&matrix_rows, &sub_shard, &mut outputs);
+
+        // Filling the missing shards.
+        for (&i, output) in missing.iter().zip(outputs) {
+            shards[i] = Some(T::from(output));
+        }
+
+        Ok(())
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is missing.
+    /// Returns all the shards including all data and parity shards. For a
+    /// non-systematic code, the shards are the ones returned by `encode`.
+    /// It is the same as `reconstruct` with empty shards in place of `None`.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// let decoded_shards = rs.decode(shards);
+    /// ```
+    pub fn decode(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards: Vec<Option<Vec<u8>>> = shards
+            .into_iter()
+            .map(|shard| (!shard.is_empty()).then_some(shard))
+            .collect();
+        self.reconstruct(&mut shards)?;
+
+        Ok(shards
+            .into_iter()
+            .map(|shard| shard.unwrap_or_default())
+            .collect())
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is
+    /// missing like `decode`, then checks the present shards which weren't
+    /// needed for the recovery against the recovered ones, so that a
+    /// silently corrupted shard isn't passed on as decoded data. This
+    /// needs more than data shard count present shards, with exactly that
+    /// many nothing can be checked. On a mismatch it fails with
+    /// `Error::ParityMismatch` and the indices of the present shards whose
+    /// corruption alone explains it, which can only be told with at least
+    /// 2 more present shards than data shards, and are empty otherwise.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::error::Error;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![5, 14, 11]];
+    /// assert!(rs.decode_checked(shards).is_ok());
+    /// let corrupted = vec![vec![0, 1, 9], vec![], vec![6, 11, 12], vec![5, 14, 11]];
+    /// assert!(matches!(rs.decode_checked(corrupted), Err(Error::ParityMismatch(_))));
+    /// ```
+    pub fn decode_checked(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let present = self.present_indices(&shards);
+        let given: Vec<Option<Vec<u8>>> = shards
+            .into_iter()
+            .map(|shard| (!shard.is_empty()).then_some(shard))
+            .collect();
+        let mut decoded = given.clone();
+        self.reconstruct(&mut decoded)?;
+        let decoded: Vec<Vec<u8>> = decoded.into_iter().flatten().collect();
+        let rows = self.verify_rows()?;
+        if self.mismatched_parity(&decoded, &rows)?.is_empty() {
+            return Ok(decoded);
+        }
+
+        // Leaving a present shard out and decoding from the others only
+        // tells something if at least one more shard than needed is left.
+        if present.len() < self.data_shard_count + 2 {
+            return Err(Error::ParityMismatch(vec![]));
+        }
+
+        Err(Error::ParityMismatch(
+            self.leave_one_out_suspects(&given, &present, &rows)?,
+        ))
+    }
+
+    /// Takes shards as input and recover the shards at the given indices,
+    /// for callers who already know which shards are lost. The contents
+    /// of those shards are ignored, so they don't have to be emptied.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    /// * `erasures` - Indices of the lost shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![0; 3], vec![6, 11, 12], vec![0; 3]];
+    /// let decoded_shards = rs.decode_with_erasures(shards, &[1, 3]);
+    /// ```
+    pub fn decode_with_erasures(
+        &self,
+        shards: Vec<Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards: Vec<Option<Vec<u8>>> = shards.into_iter().map(Some).collect();
+        for &i in erasures.iter() {
+            match shards.get_mut(i) {
+                Some(shard) => *shard = None,
+                None => return Err(Error::InvalidShardIndex(i)),
+            }
+        }
+        self.reconstruct(&mut shards)?;
+
+        Ok(shards
+            .into_iter()
+            .map(|shard| shard.unwrap_or_default())
+            .collect())
+    }
+
+    /// Takes shards as input and recover the data shards one at a time,
+    /// passing every data shard to the callback in index order as soon as
+    /// it is available. Present data shards are passed as they are and
+    /// parity shards are not rebuilt, so the first bytes are usable
+    /// before the whole decode is done. For a non-systematic code, the
+    /// data shards are the ones which were encoded.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `on_shard` - Callback taking the index and the bytes of every data shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// let mut data = vec![];
+    /// rs.decode_streaming(shards, |_, shard| data.extend_from_slice(shard)).unwrap();
+    /// assert_eq!(vec![0, 1, 2, 3, 4, 5], data);
+    /// ```
+    pub fn decode_streaming(
+        &self,
+        shards: Vec<Vec<u8>>,
+        mut on_shard: impl FnMut(usize, &[u8]),
+    ) -> Result<(), Error> {
+        let (_, shard_elem_len) = self.check_shard_sizes_for_decode(&shards)?;
+
+        if self.systematic
+            && shards[..self.data_shard_count]
+                .iter()
+                .all(|x| !x.is_empty())
+        {
+            for (i, shard) in shards[..self.data_shard_count].iter().enumerate() {
+                on_shard(i, shard);
+            }
+            return Ok(());
+        }
+
+        let mut sub_matrix_rows = self.present_indices(&shards);
+        sub_matrix_rows.truncate(self.data_shard_count);
+        let sub_shard: Vec<&[u8]> = sub_matrix_rows.iter().map(|&i| &shards[i][..]).collect();
+        let data_decode_matrix = self.decode_matrix(&sub_matrix_rows)?;
+
+        let mut output: Vec<u8> = vec![0; shard_elem_len];
+        for (i, shard) in shards.iter().enumerate().take(self.data_shard_count) {
+            if self.systematic && !shard.is_empty() {
+                on_shard(i, shard);
+            } else {
+                self.combine_row(&data_decode_matrix.data[i], &sub_shard, &mut output);
+                on_shard(i, &output);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recover the only missing shard in place without inverting a matrix.
+    /// A missing parity shard is encoded again from the data shards, and a
+    /// missing data shard is solved from the first parity row, whose
+    /// coefficients are all non zero since the code is MDS. For a
+    /// non-systematic code it falls back to `decode`.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Only the missing one can be empty.
+    /// * `missing` - Index of the missing shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// shards[1] = vec![];
+    /// rs.recover_single(&mut shards, 1).unwrap();
+    /// assert_eq!(vec![3, 4, 5], shards[1]);
+    /// ```
+    pub fn recover_single(&self, shards: &mut [Vec<u8>], missing: usize) -> Result<(), Error> {
+        if shards.len() != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        if missing >= self.total_shard_count {
+            return Err(Error::InvalidShardIndex(missing));
+        }
+        let shard_elem_len = shards[(missing + 1) % self.total_shard_count].len();
+        for (i, shard) in shards.iter().enumerate() {
+            if i == missing {
+                continue;
+            }
+            if shard.is_empty() {
+                return Err(Error::TooFewShards);
+            }
+            if shard.len() != shard_elem_len {
+                return Err(Error::InconsistentShards);
+            }
+        }
+
+        if !self.systematic {
+            let mut given = shards.to_vec();
+            given[missing] = vec![];
+            let decoded = self.decode(given)?;
+            shards[missing] = decoded[missing].clone();
+            return Ok(());
+        }
+
+        let mut output: Vec<u8> = vec![0; shard_elem_len];
+        if missing >= self.data_shard_count {
+            let row = &self.parity.data[missing - self.data_shard_count];
+            self.combine_row(row, &shards[..self.data_shard_count], &mut output);
+        } else {
+            // parity_0 = sum of row[l] * data_l, so data_missing is the sum
+            // of the other terms and parity_0, divided by row[missing].
+            let row = &self.parity.data[0];
+            let pivot = row[missing];
+            let mut coeffs: Vec<u8> = Vec::with_capacity(self.data_shard_count);
+            let mut inputs: Vec<&[u8]> = Vec::with_capacity(self.data_shard_count);
+            for l in 0..self.data_shard_count {
+                if l != missing {
+                    coeffs.push(self.gf.div(row[l], pivot));
+                    inputs.push(&shards[l]);
+                }
+            }
+            coeffs.push(self.gf.div(1, pivot));
+            inputs.push(&shards[self.data_shard_count]);
+            self.combine_row(&coeffs, &inputs, &mut output);
+        }
+        shards[missing] = output;
+
+        Ok(())
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is
+    /// missing like `decode`, but ignores any shards past the total no. of
+    /// shards instead of failing with `Error::TooManyShards`. This is only
+    /// meant to recover data after a bug upstream added shards to the end
+    /// of the set: if the extra shards are anywhere else, the shards are
+    /// shifted and the recovered data is garbage.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards, possibly followed by extra ones.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![], vec![]];
+    /// let decoded_shards = rs.decode_lenient(shards);
+    /// ```
+    pub fn decode_lenient(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards = shards;
+        shards.truncate(self.total_shard_count);
+
+        self.decode(shards)
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is
+    /// missing like `decode`, doing as few field multiplications as it
+    /// can. Present data shards are preferred over parity shards, so no
+    /// matrix is inverted when only parity shards are missing, and only the
+    /// missing shards are computed. For a non-systematic code it is the
+    /// same as `decode`.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![], vec![]];
+    /// let decoded_shards = rs.reconstruct_cheapest(shards);
+    /// ```
+    pub fn reconstruct_cheapest(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        if !self.systematic {
+            return self.decode(shards);
+        }
+        let (present, shard_elem_len) = self.check_shard_sizes_for_decode(&shards)?;
+        if present == self.total_shard_count {
+            return Ok(shards);
+        }
+
+        let mut shards = shards;
+        let missing_data: Vec<usize> = (0..self.data_shard_count)
+            .filter(|&i| shards[i].is_empty())
+            .collect();
+        if !missing_data.is_empty() {
+            // Data shards come first, so every present data shard is used.
+            let mut sub_matrix_rows = self.present_indices(&shards);
+            sub_matrix_rows.truncate(self.data_shard_count);
+            let data_decode_matrix = self.decode_matrix(&sub_matrix_rows)?;
+            let sub_shard: Vec<&[u8]> = sub_matrix_rows.iter().map(|&i| &shards[i][..]).collect();
+            let mut outputs: Vec<Vec<u8>> = vec![vec![0; shard_elem_len]; missing_data.len()];
+            for (&i, output) in missing_data.iter().zip(outputs.iter_mut()) {
+                self.combine_row(&data_decode_matrix.data[i], &sub_shard, output);
+            }
+            for (&i, output) in missing_data.iter().zip(outputs) {
+                shards[i] = output;
+            }
+        }
+
+        for j in 0..self.parity_shard_count {
+            let i = self.data_shard_count + j;
+            if shards[i].is_empty() {
+                let mut output: Vec<u8> = vec![0; shard_elem_len];
+                self.combine_row(
+                    &self.parity.data[j],
+                    &shards[..self.data_shard_count],
+                    &mut output,
+                );
+                shards[i] = output;
+            }
+        }
+
+        Ok(shards)
+    }
+
+    /// Returns the indices of the present (non-empty) shards in order.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// assert_eq!(vec![0, 2], rs.present_indices(&shards));
+    /// ```
+    pub fn present_indices(&self, shards: &[Vec<u8>]) -> Vec<usize> {
+        shards
+            .iter()
+            .enumerate()
+            .filter(|(_, shard)| !shard.is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Check whether the shard at the given index could be rebuilt from
+    /// the other present shards. Since any data shard count shards are
+    /// enough to rebuild every shard, this is true iff at least data
+    /// shard count shards other than the given one are present.
+    /// # Arguments
+    ///
+    /// * `present` - Whether each shard is present, one entry per shard.
+    /// * `index` - Index of the shard to be rebuilt.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// assert!(rs.is_shard_recoverable(&[true, false, true, false], 1));
+    /// ```
+    pub fn is_shard_recoverable(&self, present: &[bool], index: usize) -> bool {
+        if present.len() != self.total_shard_count || index >= self.total_shard_count {
+            return false;
+        }
+
+        let others = present
+            .iter()
+            .enumerate()
+            .filter(|&(i, &is_present)| i != index && is_present)
+            .count();
+
+        others >= self.data_shard_count
+    }
+
+    /// Returns how many more shards can be lost before the data can't be
+    /// recovered, given which shards are present. It is 0 if the data
+    /// can't be recovered already.
+    /// # Arguments
+    ///
+    /// * `present` - Whether each shard is present, one entry per shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// assert_eq!(1, rs.tolerable_losses(&[true, false, true, true]));
+    /// ```
+    pub fn tolerable_losses(&self, present: &[bool]) -> usize {
+        if present.len() != self.total_shard_count {
+            return 0;
+        }
+
+        let present_count = present.iter().filter(|&&is_present| is_present).count();
+        present_count.saturating_sub(self.data_shard_count)
+    }
+
+    /// Returns the indices of the shards whose loss would make the data
+    /// unrecoverable, given which shards are present. Since any data shard
+    /// count shards are enough, this is every present shard when exactly
+    /// that many are present, and none otherwise: with more shards present
+    /// one loss can be tolerated, with fewer the data is lost already.
+    /// # Arguments
+    ///
+    /// * `present` - Whether each shard is present, one entry per shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// assert_eq!(vec![0, 3], rs.critical_shards(&[true, false, false, true]));
+    /// ```
+    pub fn critical_shards(&self, present: &[bool]) -> Vec<usize> {
+        if present.len() != self.total_shard_count {
+            return vec![];
+        }
+
+        let present_indices: Vec<usize> = (0..self.total_shard_count)
+            .filter(|&i| present[i])
+            .collect();
+        if present_indices.len() != self.data_shard_count {
+            return vec![];
+        }
+
+        present_indices
+    }
+
+    /// Returns the data shards of a complete set of shards. For a
+    /// systematic code they are the first data shard count shards, for a
+    /// non-systematic code they are computed from those shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. None of them can be missing.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 11, 12], vec![5, 14, 11]];
+    /// let data_shards = rs.extract_data(shards);
+    /// ```
+    pub(crate) fn extract_data(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards = shards;
+        shards.truncate(self.data_shard_count);
+        if self.systematic {
+            return Ok(shards);
+        }
+
+        let top = self
+            .matrix
+            .new_sub_matrix(0, self.data_shard_count, 0, self.data_shard_count);
+        let top_inv = top.invert(&self.gf)?;
+        let mut data = vec![vec![0; shards[0].len()]; self.data_shard_count];
+        self.encode_shards(&top_inv, &shards, &mut data);
+
+        Ok(data)
+    }
+
+    /// Split the payload into data shards of the same length, padding the
+    /// last one with zeros, and append zeroed parity shards.
+    /// # Arguments
+    ///
+    /// * `payload` - Bytes to be split.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.split_payload(&[0, 1, 2, 3, 4]);
+    /// ```
+    pub(crate) fn split_payload(&self, payload: &[u8]) -> Vec<Vec<u8>> {
+        // Every shard needs at least one byte to be encoded.
+        let shard_elem_len = payload.len().div_ceil(self.data_shard_count).max(1);
+        self.split_payload_into(payload, shard_elem_len)
+    }
+
+    /// Split the payload into data shards of the given length, padding
+    /// them with zeros, and append zeroed parity shards.
+    /// # Arguments
+    ///
+    /// * `payload` - Bytes to be split.
+    /// * `shard_elem_len` - Length of every shard. It must be enough to hold the payload.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.split_payload_into(&[0, 1, 2, 3, 4], 4);
+    /// ```
+    pub(crate) fn split_payload_into(&self, payload: &[u8], shard_elem_len: usize) -> Vec<Vec<u8>> {
+        let mut shards: Vec<Vec<u8>> = payload
+            .chunks(shard_elem_len)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        shards.resize(self.total_shard_count, vec![]);
+        for shard in shards.iter_mut() {
+            Self::pad_shard(shard, shard_elem_len);
+        }
+
+        shards
+    }
+
+    /// Extend the shard with zeros up to the given length. A shard which
+    /// is already as long as that is left as it is.
+    /// # Arguments
+    ///
+    /// * `shard` - Shard to be padded.
+    /// * `target_len` - Length of the shard after padding.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let mut shard = vec![1, 2, 3];
+    /// ReedSolomon::pad_shard(&mut shard, 5);
+    /// assert_eq!(vec![1, 2, 3, 0, 0], shard);
+    /// ```
+    pub fn pad_shard(shard: &mut Vec<u8>, target_len: usize) {
+        if shard.len() < target_len {
+            shard.resize(target_len, 0);
+        }
+    }
+
+    /// Split the data into data shards whose length is a multiple of the
+    /// given alignment, padding them with zeros, and append zeroed parity
+    /// shards of the same length, ready to be encoded.
+    /// Returns the shards and the length of the data, which is needed to
+    /// remove the padding later.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be split.
+    /// * `alignment` - Length of every shard must be a multiple of it e.g. the sector size.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let (shards, data_len) = rs.split_aligned(&[0, 1, 2, 3, 4], 512).unwrap();
+    /// assert_eq!(512, shards[0].len());
+    /// ```
+    pub fn split_aligned(
+        &self,
+        data: &[u8],
+        alignment: usize,
+    ) -> Result<(Vec<Vec<u8>>, usize), Error> {
+        if alignment == 0 {
+            return Err(Error::ZeroAlignment);
+        }
+
+        let shard_elem_len = data.len().div_ceil(self.data_shard_count).max(1);
+        let aligned_len = shard_elem_len.div_ceil(alignment) * alignment;
+
+        Ok((self.split_payload_into(data, aligned_len), data.len()))
+    }
+
+    /// Split the data into data shards like `split_payload` does and encode
+    /// checksum shards for them, keeping the working memory within the
+    /// given budget. If the whole set of shards fits in the budget it is
+    /// encoded at once, otherwise it is encoded in stripes of columns
+    /// small enough to fit, which are appended to the returned shards.
+    /// The returned shards themselves are not counted in the budget.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be encoded.
+    /// * `max_memory` - Max no. of bytes used while encoding besides the returned shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode_budgeted(&[0, 1, 2, 3, 4], 8);
+    /// ```
+    pub fn encode_budgeted(&self, data: &[u8], max_memory: usize) -> Result<Vec<Vec<u8>>, Error> {
+        let shard_elem_len = data.len().div_ceil(self.data_shard_count).max(1);
+        if shard_elem_len.saturating_mul(self.total_shard_count) <= max_memory {
+            return self.encode(self.split_payload(data));
+        }
+
+        // Every stripe needs at least one byte of every shard.
+        let stripe_len = (max_memory / self.total_shard_count).max(1);
+        let mut shards: Vec<Vec<u8>> =
+            vec![Vec::with_capacity(shard_elem_len); self.total_shard_count];
+        for start in (0..shard_elem_len).step_by(stripe_len) {
+            let end = (start + stripe_len).min(shard_elem_len);
+            let mut stripe: Vec<Vec<u8>> = vec![vec![0; end - start]; self.total_shard_count];
+            for (i, part) in stripe[..self.data_shard_count].iter_mut().enumerate() {
+                let data_start = (i * shard_elem_len + start).min(data.len());
+                let data_end = (i * shard_elem_len + end).min(data.len());
+                part[..data_end - data_start].copy_from_slice(&data[data_start..data_end]);
+            }
+
+            for (shard, part) in shards.iter_mut().zip(self.encode(stripe)?) {
+                shard.extend_from_slice(&part);
+            }
+        }
+
+        Ok(shards)
+    }
+
+    /// Returns the exact no. of bytes returned by `encode_to_flat` for data
+    /// of the given length, so that the buffer can be allocated up front.
+    /// # Arguments
+    ///
+    /// * `data_len` - Length of the data to be encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// assert_eq!(12, rs.flat_encoded_size(5));
+    /// ```
+    pub fn flat_encoded_size(&self, data_len: usize) -> usize {
+        // Every shard needs at least one byte to be encoded.
+        let shard_elem_len = data_len.div_ceil(self.data_shard_count).max(1);
+        shard_elem_len * self.total_shard_count
+    }
+
+    /// Split the data into data shards like `split_payload` does, encode
+    /// checksum shards for them and returns all the shards joined in one
+    /// buffer, in index order.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let flat = rs.encode_to_flat(&[0, 1, 2, 3, 4]).unwrap();
+    /// assert_eq!(rs.flat_encoded_size(5), flat.len());
+    /// ```
+    pub fn encode_to_flat(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let shards = self.encode(self.split_payload(data))?;
+
+        let mut flat: Vec<u8> = Vec::with_capacity(self.flat_encoded_size(data.len()));
+        for shard in shards.iter() {
+            flat.extend_from_slice(shard);
+        }
+
+        Ok(flat)
+    }
+
+    /// Split the data into data shards behind a header holding the length
+    /// of the data, and encode checksum shards for them. The header is a
+    /// big-endian u64 at the start of the first data shard.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode_with_header(&[0, 1, 2, 3, 4]);
+    /// ```
+    pub fn encode_with_header(&self, data: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        let mut payload = vec![0; header::U64_LEN + data.len()];
+        header::write_u64_be(&mut payload, data.len() as u64);
+        payload[header::U64_LEN..].copy_from_slice(data);
+
+        self.encode(self.split_payload(&payload))
+    }
+
+    /// Recover the missing shards produced by `encode_with_header` and
+    /// returns the data, without the header and the padding.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode_with_header(&[0, 1, 2, 3, 4]).unwrap();
+    /// shards[0] = vec![];
+    /// let data = rs.decode_with_header(shards).unwrap();
+    /// assert_eq!(vec![0, 1, 2, 3, 4], data);
+    /// ```
+    pub fn decode_with_header(&self, shards: Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+        let shards = self.extract_data(self.decode(shards)?)?;
+        let payload = shards.concat();
+        if payload.len() < header::U64_LEN {
+            return Err(Error::InvalidHeader);
+        }
+
+        let data_len = header::read_u64_be(&payload) as usize;
+        let data = &payload[header::U64_LEN..];
+        if data_len > data.len() {
+            return Err(Error::InvalidHeader);
+        }
+
+        Ok(data[..data_len].to_vec())
+    }
+
+    /// Split the data into data shards with the length of the data stored
+    /// at the end of the last data shard, and encode checksum shards for
+    /// them. The length is a varint with its bytes reversed, so it can be
+    /// read backwards from the end, and the padding goes between the data
+    /// and the length. If the shards are shorter than the length, it
+    /// spans the last data shards, which is fine since it is read from
+    /// the joined data shards.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode_with_trailer(&[0, 1, 2, 3, 4]);
+    /// ```
+    pub fn encode_with_trailer(&self, data: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        let trailer = header::encode_trailing_varint(data.len() as u64);
+        let payload_len = data.len() + trailer.len();
+        let shard_elem_len = payload_len.div_ceil(self.data_shard_count);
+
+        let mut payload = data.to_vec();
+        payload.resize(shard_elem_len * self.data_shard_count - trailer.len(), 0);
+        payload.extend_from_slice(&trailer);
+
+        self.encode(self.split_payload_into(&payload, shard_elem_len))
+    }
+
+    /// Recover the missing shards produced by `encode_with_trailer` and
+    /// returns the original data, using the length stored at the end of
+    /// the data shards to remove the padding.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode_with_trailer(&[0, 1, 2, 3, 4]).unwrap();
+    /// shards[1] = vec![];
+    /// assert_eq!(vec![0, 1, 2, 3, 4], rs.decode_with_trailer(shards).unwrap());
+    /// ```
+    pub fn decode_with_trailer(&self, shards: Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+        let payload = self.extract_data(self.decode(shards)?)?.concat();
+        let (data_len, trailer_len) = match header::read_trailing_varint(&payload) {
+            Some(x) => x,
+            None => return Err(Error::InvalidHeader),
+        };
+        let data_len = data_len as usize;
+        if data_len > payload.len() - trailer_len {
+            return Err(Error::InvalidHeader);
+        }
+
+        Ok(payload[..data_len].to_vec())
+    }
+
+    /// Split the data into data shards of the same length, padding the
+    /// last one with zeros, and append zeroed parity shards, ready to be
+    /// encoded. Keep the length of the data to `join` the shards later.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be split.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.split(&[0, 1, 2, 3, 4]);
+    /// assert_eq!(vec![vec![0, 1, 2], vec![3, 4, 0], vec![0; 3], vec![0; 3]], shards);
+    /// ```
+    pub fn split(&self, data: &[u8]) -> Vec<Vec<u8>> {
+        self.split_payload(data)
+    }
+
+    /// Join the data shards of a complete set of shards and remove the
+    /// padding added by `split`.
+    /// Returns the original data.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. None of them can be missing.
+    /// * `original_len` - Length of the data which was split.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode(rs.split(&[0, 1, 2, 3, 4])).unwrap();
+    /// assert_eq!(vec![0, 1, 2, 3, 4], rs.join(&shards, 5).unwrap());
+    /// ```
+    pub fn join(&self, shards: &[Vec<u8>], original_len: usize) -> Result<Vec<u8>, Error> {
+        self.check_shard_sizes(shards)?;
+        let capacity = shards[0].len() * self.data_shard_count;
+        if original_len > capacity {
+            return Err(Error::DataLenOutOfBounds(original_len, capacity));
+        }
+
+        let mut data = if self.systematic {
+            shards[..self.data_shard_count].concat()
+        } else {
+            self.extract_data(shards[..self.data_shard_count].to_vec())?
+                .concat()
+        };
+        data.truncate(original_len);
+
+        Ok(data)
+    }
+
+    /// Takes shards as input, recover only the missing data shards and
+    /// join them like `join`, i.e. gives back the original data in one
+    /// call. The missing parity shards are not recovered.
+    /// Returns the original data.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `original_len` - Length of the data which was split.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode(rs.split(&[0, 1, 2, 3, 4])).unwrap();
+    /// shards[0] = vec![];
+    /// assert_eq!(vec![0, 1, 2, 3, 4], rs.decode_to_bytes(shards, 5).unwrap());
+    /// ```
+    pub fn decode_to_bytes(
+        &self,
+        shards: Vec<Vec<u8>>,
+        original_len: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let mut shards: Vec<Option<Vec<u8>>> = shards
+            .into_iter()
+            .map(|shard| (!shard.is_empty()).then_some(shard))
+            .collect();
+        self.reconstruct_data(&mut shards)?;
+        shards.truncate(self.data_shard_count);
+        let shards: Vec<Vec<u8>> = shards.into_iter().flatten().collect();
+        let capacity = shards[0].len() * self.data_shard_count;
+        if original_len > capacity {
+            return Err(Error::DataLenOutOfBounds(original_len, capacity));
+        }
+
+        let mut data = self.extract_data(shards)?.concat();
+        data.truncate(original_len);
+
+        Ok(data)
+    }
+
+    /// Join the data shards of a complete set of shards and remove the
+    /// trailing zeros, as a best effort to remove the padding when the
+    /// length of the original data was not stored.
+    /// This is ambiguous: if the original data itself ended in zeros, they
+    /// are removed as well. Use `encode_with_header` for new data instead.
+    /// Returns an empty Vec if any data shard is missing, so decode the
+    /// shards first.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. None of the data shards can be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode(vec![vec![1, 2, 3], vec![4, 0, 0], vec![0; 3], vec![0; 3]]).unwrap();
+    /// assert_eq!(vec![1, 2, 3, 4], rs.join_trim_trailing_zeros(&shards));
+    /// ```
+    pub fn join_trim_trailing_zeros(&self, shards: &[Vec<u8>]) -> Vec<u8> {
+        if shards.len() < self.data_shard_count
+            || shards[..self.data_shard_count]
+                .iter()
+                .any(|shard| shard.is_empty())
+        {
+            return vec![];
+        }
+
+        let mut data = match self.extract_data(shards.to_vec()) {
+            Ok(x) => x.concat(),
+            Err(_) => return vec![],
+        };
+        let data_len = data.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+        data.truncate(data_len);
+
+        data
+    }
+
+    /// Regroup any no. of pieces into data shards and encode checksum
+    /// shards for them. The pieces are concatenated behind a table of
+    /// their lengths, so `decode_chunked` can give back the same pieces.
+    /// The table is the no. of pieces followed by the length of every
+    /// piece, all big-endian u64.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `pieces` - Pieces to be encoded, any no. of them and of any length.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.encode_chunked(&[vec![0, 1], vec![2], vec![3, 4, 5]]);
+    /// ```
+    pub fn encode_chunked(&self, pieces: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Error> {
+        let table_len = (pieces.len() + 1) * header::U64_LEN;
+        let mut payload = vec![0; table_len];
+        header::write_u64_be(&mut payload, pieces.len() as u64);
+        for (i, piece) in pieces.iter().enumerate() {
+            header::write_u64_be(
+                &mut payload[(i + 1) * header::U64_LEN..],
+                piece.len() as u64,
+            );
+        }
+        for piece in pieces.iter() {
+            payload.extend_from_slice(piece);
+        }
+
+        self.encode(self.split_payload(&payload))
+    }
+
+    /// Recover the missing shards produced by `encode_chunked` and returns
+    /// the encoded pieces.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.encode_chunked(&[vec![0, 1], vec![2], vec![3, 4, 5]]).unwrap();
+    /// shards[0] = vec![];
+    /// assert_eq!(vec![vec![0, 1], vec![2], vec![3, 4, 5]], rs.decode_chunked(shards).unwrap());
+    /// ```
+    pub fn decode_chunked(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let payload = self.extract_data(self.decode(shards)?)?.concat();
+        if payload.len() < header::U64_LEN {
+            return Err(Error::InvalidHeader);
+        }
+
+        let piece_count = header::read_u64_be(&payload) as usize;
+        let table_len = match piece_count
+            .checked_add(1)
+            .and_then(|x| x.checked_mul(header::U64_LEN))
+        {
+            Some(x) if x <= payload.len() => x,
+            _ => return Err(Error::InvalidHeader),
+        };
+
+        let mut pieces: Vec<Vec<u8>> = Vec::with_capacity(piece_count);
+        let mut start = table_len;
+        for i in 0..piece_count {
+            let piece_len = header::read_u64_be(&payload[(i + 1) * header::U64_LEN..]) as usize;
+            let end = match start.checked_add(piece_len) {
+                Some(x) if x <= payload.len() => x,
+                _ => return Err(Error::InvalidHeader),
+            };
+            pieces.push(payload[start..end].to_vec());
+            start = end;
+        }
+
+        Ok(pieces)
+    }
+
+    /// Split the data into data shards, encode checksum shards for them
+    /// and put a header in front of every shard. The header holds the
+    /// index of the shard, the no. of data and parity shards and the
+    /// length of the data, so `restore` can check the shards and remove
+    /// the padding without any other information.
+    /// Returns all the archived shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be archived.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = rs.archive(&[0, 1, 2, 3, 4]);
+    /// ```
+    pub fn archive(&self, data: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        let shards = self.encode(self.split_payload(data))?;
+
+        Ok(shards
+            .into_iter()
+            .enumerate()
+            .map(|(i, shard)| {
+                let header = header::ArchiveHeader {
+                    index: i as u64,
+                    data_shards: self.data_shard_count as u64,
+                    parity_shards: self.parity_shard_count as u64,
+                    data_len: data.len() as u64,
+                };
+                let mut archived = vec![0; header::ARCHIVE_HEADER_LEN];
+                header.write(&mut archived);
+                archived.extend(shard);
+                archived
+            })
+            .collect())
+    }
+
+    /// Recover the missing shards produced by `archive` and returns the
+    /// archived data. The header of every present shard is checked against
+    /// the code and its position.
+    /// # Arguments
+    ///
+    /// * `shards` - Given archived shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut shards = rs.archive(&[0, 1, 2, 3, 4]).unwrap();
+    /// shards[1] = vec![];
+    /// shards[3] = vec![];
+    /// assert_eq!(vec![0, 1, 2, 3, 4], rs.restore(shards).unwrap());
+    /// ```
+    pub fn restore(&self, shards: Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+        let mut data_len: Option<u64> = None;
+        let mut bodies: Vec<Vec<u8>> = Vec::with_capacity(shards.len());
+        for (i, shard) in shards.into_iter().enumerate() {
+            if shard.is_empty() {
+                bodies.push(shard);
+                continue;
+            }
+
+            let header = match header::ArchiveHeader::read(&shard) {
+                Some(x) => x,
+                None => return Err(Error::InvalidHeader),
+            };
+            if header.index != i as u64
+                || header.data_shards != self.data_shard_count as u64
+                || header.parity_shards != self.parity_shard_count as u64
+                || data_len.is_some_and(|len| len != header.data_len)
+            {
+                return Err(Error::InvalidHeader);
+            }
+            data_len = Some(header.data_len);
+            bodies.push(shard[header::ARCHIVE_HEADER_LEN..].to_vec());
+        }
+
+        let data_len = match data_len {
+            Some(x) => x as usize,
+            None => return Err(Error::TooFewShards),
+        };
+        let data = self.extract_data(self.decode(bodies)?)?.concat();
+        if data_len > data.len() {
+            return Err(Error::InvalidHeader);
+        }
+
+        Ok(data[..data_len].to_vec())
+    }
+
+    /// Split the data into data shards, encode checksum shards for them and
+    /// write every shard to `basename.<index>` in the given directory,
+    /// along with `basename.meta` holding the length of the data, the no.
+    /// of data shards and the no. of parity shards as big-endian u64.
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to be encoded.
+    /// * `dir` - Directory the files are written to. It must exist.
+    /// * `basename` - Name of the files without the extension.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::path::Path;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// rs.encode_to_dir(&[0, 1, 2, 3, 4], Path::new("shards"), "data").unwrap();
+    /// ```
+    pub fn encode_to_dir(&self, data: &[u8], dir: &Path, basename: &str) -> Result<(), Error> {
+        let shards = self.encode(self.split_payload(data))?;
+
+        let mut meta = [0u8; 3 * header::U64_LEN];
+        header::write_u64_be(&mut meta, data.len() as u64);
+        header::write_u64_be(&mut meta[header::U64_LEN..], self.data_shard_count as u64);
+        header::write_u64_be(
+            &mut meta[2 * header::U64_LEN..],
+            self.parity_shard_count as u64,
+        );
+        fs::write(dir.join(format!("{}.meta", basename)), meta)?;
+
+        for (i, shard) in shards.iter().enumerate() {
+            fs::write(dir.join(format!("{}.{}", basename, i)), shard)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the files written by `encode_to_dir`, recover the missing
+    /// shard files and returns the original data.
+    /// # Arguments
+    ///
+    /// * `dir` - Directory the files were written to.
+    /// * `basename` - Name of the files without the extension.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::path::Path;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = rs.decode_from_dir(Path::new("shards"), "data").unwrap();
+    /// ```
+    pub fn decode_from_dir(&self, dir: &Path, basename: &str) -> Result<Vec<u8>, Error> {
+        let meta = fs::read(dir.join(format!("{}.meta", basename)))?;
+        if meta.len() != 3 * header::U64_LEN
+            || header::read_u64_be(&meta[header::U64_LEN..]) != self.data_shard_count as u64
+            || header::read_u64_be(&meta[2 * header::U64_LEN..]) != self.parity_shard_count as u64
+        {
+            return Err(Error::InvalidHeader);
+        }
+        let data_len = header::read_u64_be(&meta) as usize;
+
+        let mut shards: Vec<Vec<u8>> = Vec::with_capacity(self.total_shard_count);
+        for i in 0..self.total_shard_count {
+            match fs::read(dir.join(format!("{}.{}", basename, i))) {
+                Ok(shard) => shards.push(shard),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => shards.push(vec![]),
+                Err(e) => return Err(Error::Io(e)),
+            }
+        }
+
+        let payload = self.extract_data(self.decode(shards)?)?.concat();
+        if data_len > payload.len() {
+            return Err(Error::InvalidHeader);
+        }
+
+        Ok(payload[..data_len].to_vec())
+    }
+
+    /// Encodes checksum shards like `encode` and then whitens every shard
+    /// by XORing it with a keystream derived from the seed and the index
+    /// of the shard. Whitening only scrambles the bytes so that runs of
+    /// equal bytes don't reach storage, it is NOT encryption and gives
+    /// no confidentiality.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    /// * `seed` - Seed of the keystream. The same seed must be passed to `decode_whitened`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0, 0, 0], vec![0, 0, 0]];
+    /// let whitened_shards = rs.encode_whitened(shards, 42);
+    /// ```
+    pub fn encode_whitened(&self, shards: Vec<Vec<u8>>, seed: u64) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards = self.encode(shards)?;
+        for (i, shard) in shards.iter_mut().enumerate() {
+            whiten::whiten(shard, seed, i);
+        }
+
+        Ok(shards)
+    }
+
+    /// Un-whitens the present shards produced by `encode_whitened` and
+    /// recovers the missing ones like `decode`.
+    /// Returns all the un-whitened shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given whitened shards including data and parity shards. Some shards might be missing.
+    /// * `seed` - Seed of the keystream used by `encode_whitened`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0, 0, 0], vec![0, 0, 0]];
+    /// let mut whitened_shards = rs.encode_whitened(shards, 42).unwrap();
+    /// whitened_shards[0] = vec![];
+    /// let decoded_shards = rs.decode_whitened(whitened_shards, 42);
+    /// ```
+    pub fn decode_whitened(&self, shards: Vec<Vec<u8>>, seed: u64) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards = shards;
+        for (i, shard) in shards.iter_mut().enumerate() {
+            whiten::whiten(shard, seed, i);
+        }
+
+        self.decode(shards)
+    }
+
+    /// Takes replicas of shards as input, picks the majority copy of every
+    /// shard and recover any data or parity shards that is missing. Every
+    /// byte of a shard is the byte most of its replicas agree on, so a
+    /// minority of corrupted replicas is out-voted. On a tie, the byte
+    /// which got its votes first wins.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `replicas` - Pairs of shard index and a replica of that shard, in any order.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let replicas = vec![(0, vec![0, 1, 2]), (0, vec![0, 1, 2]), (0, vec![9, 1, 2]), (3, vec![5, 14, 11])];
+    /// let decoded_shards = rs.decode_voted(replicas);
+    /// ```
+    pub fn decode_voted(&self, replicas: Vec<(usize, Vec<u8>)>) -> Result<Vec<Vec<u8>>, Error> {
+        let mut copies: Vec<Vec<Vec<u8>>> = vec![vec![]; self.total_shard_count];
+        for (index, replica) in replicas {
+            if index >= self.total_shard_count {
+                return Err(Error::InvalidShardIndex(index));
+            }
+            copies[index].push(replica);
+        }
+
+        let mut shards: Vec<Vec<u8>> = Vec::with_capacity(self.total_shard_count);
+        for shard_copies in copies.iter() {
+            shards.push(Self::vote(shard_copies)?);
+        }
+
+        self.decode(shards)
+    }
+
+    /// Returns the byte-wise majority of the given copies of a shard, or
+    /// an empty shard if there is no copy.
+    /// # Arguments
+    ///
+    /// * `copies` - Copies of the same shard.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let shard = ReedSolomon::vote(&[vec![0, 1], vec![0, 1], vec![9, 1]]);
+    /// ```
+    fn vote(copies: &[Vec<u8>]) -> Result<Vec<u8>, Error> {
+        let shard_elem_len = match copies.first() {
+            Some(copy) => copy.len(),
+            None => return Ok(vec![]),
+        };
+        if copies.iter().any(|copy| copy.len() != shard_elem_len) {
+            return Err(Error::InconsistentShards);
+        }
+
+        let mut shard = vec![0; shard_elem_len];
+        let mut counts = [0usize; 256];
+        for i in 0..shard_elem_len {
+            counts.fill(0);
+            let mut best = copies[0][i];
+            for copy in copies.iter() {
+                let byte = copy[i];
+                counts[byte as usize] += 1;
+                if counts[byte as usize] > counts[best as usize] {
+                    best = byte;
+                }
+            }
+            shard[i] = best;
+        }
+
+        Ok(shard)
+    }
+
+    /// Returns the XOR difference of every shard of two encoded shard sets.
+    /// Since the code is linear, the difference is itself a valid encoding
+    /// of the difference of the data, and `combine` applies it to the old
+    /// set to get the new one back.
+    /// # Arguments
+    ///
+    /// * `old` - All shards of the old version including data and parity shards.
+    /// * `new` - All shards of the new version including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let old = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// let new = rs.encode(vec![vec![0, 1, 2], vec![3, 9, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// let delta = rs.shard_delta(&old, &new);
+    /// ```
+    pub fn shard_delta(&self, old: &[Vec<u8>], new: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Error> {
+        self.xor_shards(old, new)
+    }
+
+    /// Applies a difference computed by `shard_delta` to a shard set.
+    /// Returns the shard set the difference was computed against.
+    /// # Arguments
+    ///
+    /// * `old` - All shards of the old version including data and parity shards.
+    /// * `delta` - Difference of every shard returned by `shard_delta`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let old = rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// let new = rs.encode(vec![vec![0, 1, 2], vec![3, 9, 5], vec![0; 3], vec![0; 3]]).unwrap();
+    /// let delta = rs.shard_delta(&old, &new).unwrap();
+    /// assert_eq!(new, rs.combine(&old, &delta).unwrap());
+    /// ```
+    pub fn combine(&self, old: &[Vec<u8>], delta: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Error> {
+        self.xor_shards(old, delta)
+    }
+
+    /// Returns the XOR of every pair of shards of two shard sets.
+    /// # Arguments
+    ///
+    /// * `left` - All shards including data and parity shards.
+    /// * `right` - All shards including data and parity shards, of the same length as left.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(1, 1).unwrap();
+    /// let xor = rs.xor_shards(&[vec![1], vec![1]], &[vec![3], vec![3]]);
+    /// ```
+    fn xor_shards(&self, left: &[Vec<u8>], right: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Error> {
+        self.check_shard_sizes(left)?;
+        self.check_shard_sizes(right)?;
+        if left[0].len() != right[0].len() {
+            return Err(Error::InconsistentShards);
+        }
+
+        Ok(left
+            .iter()
+            .zip(right.iter())
+            .map(|(l, r)| {
+                l.iter()
+                    .zip(r.iter())
+                    .map(|(&a, &b)| GaloisField::add(a, b))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Computes a hash of every shard, usable as the key of the shard in a
+    /// content-addressed store. The hash is the 64 bit FNV-1a hash, which
+    /// is fast but not cryptographic. Missing (empty) shards get
+    /// `MISSING_SHARD_HASH`.
+    /// # Arguments
+    ///
+    /// * `shards` - Shards to be hashed. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let hashes = rs.shard_hashes(&[vec![0, 1, 2], vec![], vec![6, 11, 12], vec![5, 14, 11]]);
+    /// assert_eq!(ReedSolomon::MISSING_SHARD_HASH, hashes[1]);
+    /// ```
+    pub fn shard_hashes(&self, shards: &[Vec<u8>]) -> Vec<u64> {
+        shards
+            .iter()
+            .map(|shard| {
+                if shard.is_empty() {
+                    Self::MISSING_SHARD_HASH
+                } else {
+                    checksum::fnv1a64(shard)
+                }
+            })
+            .collect()
+    }
+
+    /// Computes a cheap checksum over the whole set of shards, which can be
+    /// compared before running a full decode or verify to detect most
+    /// corruption. It is the wrapping sum of the CRC-64 of every shard
+    /// prefixed with its index, so that swapped shards are detected as
+    /// well. The CRC is affine, so with a XOR instead of the sum swapped
+    /// shards would still give the same checksum.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let (shards, checksum) = rs
+    ///     .encode_with_checksum(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]])
+    ///     .unwrap();
+    /// assert_eq!(checksum, rs.quick_checksum(&shards));
+    /// ```
+    pub fn quick_checksum(&self, shards: &[Vec<u8>]) -> u64 {
+        shards.iter().enumerate().fold(0, |checksum, (i, shard)| {
+            checksum.wrapping_add(checksum::crc64(&[&(i as u16).to_le_bytes(), shard]))
+        })
+    }
+
+    /// Encodes checksum shards like `encode` and computes `quick_checksum`
+    /// of the encoded shards, to be stored along with them.
+    /// Returns all the shards including all data and parity shards, and
+    /// the checksum of them.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let result = rs.encode_with_checksum(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]);
+    /// ```
+    pub fn encode_with_checksum(&self, shards: Vec<Vec<u8>>) -> Result<(Vec<Vec<u8>>, u64), Error> {
+        let shards = self.encode(shards)?;
+        let checksum = self.quick_checksum(&shards);
+
+        Ok((shards, checksum))
+    }
+
+    /// Takes shards as input, with `None` for every missing shard, and
+    /// recover any data or parity shards that is missing. It is the same
+    /// as `reconstruct`, except that it returns the shards and treats
+    /// empty shards as missing too, like `decode` does.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![Some(vec![0, 1, 2]), None, Some(vec![6, 11, 12]), None];
+    /// let decoded_shards = rs.decode_options(shards);
+    /// ```
+    pub fn decode_options(&self, shards: Vec<Option<Vec<u8>>>) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards: Vec<Option<Vec<u8>>> = shards
+            .into_iter()
+            .map(|shard| shard.filter(|x| !x.is_empty()))
+            .collect();
+        self.reconstruct(&mut shards)?;
+
+        Ok(shards
+            .into_iter()
+            .map(|shard| shard.unwrap_or_default())
+            .collect())
+    }
+
+    /// Takes shards tagged with their index as input, in any order, e.g.
+    /// as they arrive from the network, and recover any data or parity
+    /// shards that is missing. At least data shard count shards must be
+    /// given and every index at most once.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Pairs of shard index and shard, in any order.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards: [(usize, &[u8]); 2] = [(3, &[5, 14, 11]), (0, &[0, 1, 2])];
+    /// let decoded_shards = rs.decode_indexed(&shards).unwrap();
+    /// assert_eq!(vec![3, 4, 5], decoded_shards[1]);
+    /// ```
+    pub fn decode_indexed(&self, shards: &[(usize, &[u8])]) -> Result<Vec<Vec<u8>>, Error> {
+        let mut placed = self.place_indexed(shards)?;
+        self.reconstruct(&mut placed)?;
+
+        Ok(placed
+            .into_iter()
+            .map(|shard| shard.map_or_else(Vec::new, Cow::into_owned))
+            .collect())
+    }
+
+    /// Takes any data shard count or more shards tagged with their index
+    /// as input, in any order, and recover only the data shards, e.g. on
+    /// the read path when a few nodes are unavailable. For a
+    /// non-systematic code, the data shards are the first data shard
+    /// count shards returned by `encode`.
+    /// Returns the data shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Pairs of shard index and shard, in any order.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards: [(usize, &[u8]); 2] = [(3, &[5, 14, 11]), (2, &[6, 11, 12])];
+    /// let data = rs.decode_data_indexed(&shards).unwrap();
+    /// assert_eq!(vec![vec![0, 1, 2], vec![3, 4, 5]], data);
+    /// ```
+    pub fn decode_data_indexed(&self, shards: &[(usize, &[u8])]) -> Result<Vec<Vec<u8>>, Error> {
+        let mut placed = self.place_indexed(shards)?;
+        self.reconstruct_data(&mut placed)?;
+        placed.truncate(self.data_shard_count);
+
+        Ok(placed
+            .into_iter()
+            .map(|shard| shard.map_or_else(Vec::new, Cow::into_owned))
+            .collect())
+    }
+
+    /// Put the shards tagged with their index at their positions, with
+    /// `None` for every shard which isn't given, without copying them.
+    /// # Arguments
+    ///
+    /// * `shards` - Pairs of shard index and shard, in any order.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let placed = rs.place_indexed(&[(3, &[5, 14, 11])]);
+    /// ```
+    fn place_indexed<'a>(
+        &self,
+        shards: &[(usize, &'a [u8])],
+    ) -> Result<Vec<Option<Cow<'a, [u8]>>>, Error> {
+        let mut placed: Vec<Option<Cow<[u8]>>> = vec![None; self.total_shard_count];
+        for &(index, shard) in shards.iter() {
+            match placed.get_mut(index) {
+                None => return Err(Error::InvalidShardIndex(index)),
+                Some(Some(_)) => return Err(Error::DuplicateShardIndex(index)),
+                Some(slot) => *slot = Some(Cow::Borrowed(shard)),
+            }
+        }
+
+        Ok(placed)
+    }
+
+    /// Takes shards as input and recover as many shards as possible.
+    /// Returns `Some` for every shard which is present or could be
+    /// recovered and `None` for the rest. When fewer than data shard
+    /// count shards are present no missing shard can be recovered, but
+    /// the present shards are still returned instead of an error.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![], vec![]];
+    /// let partial_shards = rs.decode_partial(shards);
+    /// ```
+    pub fn decode_partial(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>, Error> {
+        match self.check_shard_sizes_for_decode(&shards) {
+            // The shard count is right, so the only reason for
+            // TooFewShards is that not enough shards are present.
+            Err(Error::TooFewShards) if shards.len() == self.total_shard_count => Ok(shards
+                .into_iter()
+                .map(|shard| if shard.is_empty() { None } else { Some(shard) })
+                .collect()),
+            Err(e) => Err(e),
+            Ok(_) => Ok(self.decode(shards)?.into_iter().map(Some).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let exp_res: [[u8; 4]; 6] = [
+            [1, 0, 0, 0],
+            [0, 1, 0, 0],
+            [0, 0, 1, 0],
+            [0, 0, 0, 1],
+            [27, 28, 18, 20],
+            [28, 27, 20, 18],
+        ];
+
+        for (row_index, row) in rs.matrix.data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+        for (row_index, row) in rs.parity.data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[rs.data_shard_count + row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_encode() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard_result = rs.encode(shards);
+        let encoded_shard = match encoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let exp_res: [[u8; 3]; 4] = [[0, 1, 2], [3, 4, 5], [6, 11, 12], [5, 14, 11]];
+        for (row_index, row) in encoded_shard.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_decode_missing_data() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard_result = rs.encode(shards.clone());
+        let encoded_shard = match encoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let broken_shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![5, 14, 11]];
+        let decoded_shard_result = rs.decode(broken_shards);
+        let decoded_shard = match decoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for (row_index, row) in decoded_shard.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(encoded_shard[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_decode_missing_parity() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard_result = rs.encode(shards.clone());
+        let encoded_shard = match encoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let broken_shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 11, 12], vec![]];
+        let decoded_shard_result = rs.decode(broken_shards);
+        let decoded_shard = match decoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for (row_index, row) in decoded_shard.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(encoded_shard[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_decode_missing_data_and_parity() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard_result = rs.encode(shards.clone());
+        let encoded_shard = match encoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let broken_shards = vec![vec![0, 1, 2], vec![], vec![], vec![5, 14, 11]];
+        let decoded_shard_result = rs.decode(broken_shards);
+        let decoded_shard = match decoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for (row_index, row) in decoded_shard.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(encoded_shard[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_decode_partial() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![9, 10, 11],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+        ];
+        let encoded_shard_result = rs.encode(shards);
+        let encoded_shard = match encoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let broken_shards = vec![
+            encoded_shard[0].clone(),
+            vec![],
+            encoded_shard[2].clone(),
+            vec![],
+            vec![],
+            encoded_shard[5].clone(),
+        ];
+        let decoded_shard_result = rs.decode_partial(broken_shards);
+        let decoded_shard = match decoded_shard_result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(Some(encoded_shard[0].clone()), decoded_shard[0]);
+        assert_eq!(None, decoded_shard[1]);
+        assert_eq!(Some(encoded_shard[2].clone()), decoded_shard[2]);
+        assert_eq!(None, decoded_shard[3]);
+        assert_eq!(None, decoded_shard[4]);
+        assert_eq!(Some(encoded_shard[5].clone()), decoded_shard[5]);
+
+        let recoverable_shards = vec![
+            encoded_shard[0].clone(),
+            vec![],
+            encoded_shard[2].clone(),
+            vec![],
+            encoded_shard[4].clone(),
+            encoded_shard[5].clone(),
+        ];
+        let decoded_shard = match rs.decode_partial(recoverable_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for (row_index, row) in decoded_shard.iter().enumerate() {
+            assert_eq!(Some(encoded_shard[row_index].clone()), *row);
+        }
+
+        assert!(rs.decode_partial(vec![vec![0, 1, 2]; 5]).is_err());
+    }
+    #[test]
+    fn test_encode_decode_whitened() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard = match rs.encode(shards.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let whitened_shard = match rs.encode_whitened(shards, 42) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_ne!(encoded_shard, whitened_shard);
+
+        let mut broken_shards = whitened_shard;
+        broken_shards[1] = vec![];
+        let decoded_shard = match rs.decode_whitened(broken_shards, 42) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+    }
+    #[test]
+    fn test_generator_matrix() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let generator = rs.generator_matrix();
+        let exp_res: [[u8; 6]; 4] = [
+            [1, 0, 0, 0, 27, 28],
+            [0, 1, 0, 0, 28, 27],
+            [0, 0, 1, 0, 18, 20],
+            [0, 0, 0, 1, 20, 18],
+        ];
+
+        assert_eq!(generator.data.len(), 4);
+        for (row_index, row) in generator.data.iter().enumerate() {
+            assert_eq!(row.len(), 6);
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_apply_row() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let mut out = vec![0; 3];
+        if let Err(e) = rs.apply_row(&[3, 7], &shards, &mut out) {
+            panic!("{}", e);
+        }
+        // 3*1 ^ 7*4, 3*2 ^ 7*5, 3*3 ^ 7*6 in GF(2^8)
+        let exp_res: [u8; 3] = [3 ^ 28, 6 ^ 27, 5 ^ 18];
+        assert_eq!(exp_res[..], out[..]);
+
+        assert!(rs.apply_row(&[3], &shards, &mut out).is_err());
+        assert!(rs.apply_row(&[3, 7], &shards, &mut [0; 2]).is_err());
+    }
+    #[test]
+    fn test_is_shard_recoverable() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        // Data shard
+        assert!(rs.is_shard_recoverable(&[false, true, true, true, true, true], 0));
+        assert!(rs.is_shard_recoverable(&[false, true, true, true, true, false], 0));
+        assert!(rs.is_shard_recoverable(&[true, true, true, true, true, false], 0));
+        assert!(!rs.is_shard_recoverable(&[false, true, false, true, true, false], 0));
+        assert!(!rs.is_shard_recoverable(&[true, true, false, true, false, false], 0));
+
+        // Parity shard
+        assert!(rs.is_shard_recoverable(&[true, true, true, true, false, false], 5));
+        assert!(rs.is_shard_recoverable(&[true, false, true, true, true, false], 5));
+        assert!(!rs.is_shard_recoverable(&[true, false, true, true, false, false], 5));
+        assert!(!rs.is_shard_recoverable(&[false, false, true, true, true, true], 5));
+
+        // Malformed input
+        assert!(!rs.is_shard_recoverable(&[true, true, true, true, true, true], 6));
+        assert!(!rs.is_shard_recoverable(&[true, true, true, true, true], 0));
+    }
+    #[test]
+    fn test_encode_decode_with_header() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<u8> = (0..=20).collect();
+        let encoded_shard = match rs.encode_with_header(&data) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(5, encoded_shard.len());
+        assert_eq!([0, 0, 0, 0, 0, 0, 0, 21], encoded_shard[0][..8]);
+
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[0] = vec![];
+        broken_shards[3] = vec![];
+        let decoded_data = match rs.decode_with_header(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(data, decoded_data);
+
+        let mut bad_header = encoded_shard;
+        bad_header[0][..8].copy_from_slice(&[0, 0, 0, 0, 0, 0, 1, 0]);
+        assert!(matches!(
+            rs.decode_with_header(bad_header),
+            Err(Error::InvalidHeader)
+        ));
+    }
+    #[test]
+    fn test_decode_with_header_non_systematic() {
+        let result = ReedSolomonBuilder::new(3, 2).systematic(false).build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<u8> = (0..=20).collect();
+        let mut shards = match rs.encode_with_header(&data) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        shards[1] = vec![];
+        let decoded_data = match rs.decode_with_header(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(data, decoded_data);
+    }
+    #[test]
+    fn test_decode_voted() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let replicas = vec![
+            (3, encoded_shard[3].clone()),
+            (0, encoded_shard[0].clone()),
+            (0, vec![7, 1, 9]),
+            (0, encoded_shard[0].clone()),
+        ];
+        let decoded_shard = match rs.decode_voted(replicas) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+
+        assert!(matches!(
+            rs.decode_voted(vec![(4, vec![0, 1, 2])]),
+            Err(Error::InvalidShardIndex(4))
+        ));
+        assert!(matches!(
+            rs.decode_voted(vec![(0, vec![0, 1, 2]), (0, vec![0, 1])]),
+            Err(Error::InconsistentShards)
+        ));
+    }
+    #[test]
+    fn test_shard_delta() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+        shards.extend(vec![vec![0, 0, 0]; 2]);
+        let old = match rs.encode(shards.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        shards[2] = vec![60, 70, 80];
+        let new = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let delta = match rs.shard_delta(&old, &new) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(vec![0, 0, 0], delta[0]);
+        assert_eq!(vec![6 ^ 60, 7 ^ 70, 8 ^ 80], delta[2]);
+        // The delta is a valid encoding of the data difference.
+        let encoded_delta = match rs.encode(delta.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(delta, encoded_delta);
+
+        let combined = match rs.combine(&old, &delta) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(new, combined);
+
+        assert!(rs.shard_delta(&old, &new[..5]).is_err());
+        assert!(rs.shard_delta(&old, &vec![vec![0, 0]; 6]).is_err());
+    }
+    #[test]
+    fn test_new_shard_count_limits() {
+        let result = ReedSolomon::new(255, 1);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(256, rs.total_shard_count);
+
+        assert!(matches!(
+            ReedSolomon::new(256, 1),
+            Err(Error::TooManyShardsForField(257, 256))
+        ));
+        assert!(matches!(
+            ReedSolomon::new(1, 256),
+            Err(Error::TooManyShardsForField(257, 256))
+        ));
+        assert!(matches!(
+            ReedSolomon::new(usize::MAX, 1),
+            Err(Error::ShardsOverflow)
+        ));
+    }
+    #[test]
+    fn test_split_aligned() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+        let (shards, data_len) = match rs.split_aligned(&data, 512) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(3000, data_len);
+        assert_eq!(6, shards.len());
+        for shard in shards.iter() {
+            assert_eq!(0, shard.len() % 512);
+            assert_eq!(1024, shard.len());
+        }
+        let joined: Vec<u8> = shards[..4].concat();
+        assert_eq!(data[..], joined[..data_len]);
+        assert!(joined[data_len..].iter().all(|&b| b == 0));
+
+        let (shards, data_len) = match rs.split_aligned(&[], 512) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(0, data_len);
+        assert_eq!(512, shards[0].len());
+
+        assert!(matches!(
+            rs.split_aligned(&data, 0),
+            Err(Error::ZeroAlignment)
+        ));
+    }
+    #[test]
+    fn test_archive_restore() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<u8> = (0..=100).collect();
+        let archived = match rs.archive(&data) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(6, archived.len());
+
+        let mut broken_shards = archived.clone();
+        broken_shards[4] = vec![];
+        broken_shards[5] = vec![];
+        let restored = match rs.restore(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(data, restored);
+
+        let mut broken_shards = archived.clone();
+        broken_shards[0] = vec![];
+        broken_shards[3] = vec![];
+        let restored = match rs.restore(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(data, restored);
+
+        let mut swapped_shards = archived.clone();
+        swapped_shards.swap(0, 1);
+        assert!(matches!(
+            rs.restore(swapped_shards),
+            Err(Error::InvalidHeader)
+        ));
+        let other_rs = match ReedSolomon::new(3, 3) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(matches!(
+            other_rs.restore(archived),
+            Err(Error::InvalidHeader)
+        ));
+    }
+    #[test]
+    fn test_with_custom_parity() {
+        let gf8 = GaloisField::new();
+        let result = ReedSolomon::with_custom_parity(2, vec![vec![1, 1], vec![1, 2]], gf8);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![vec![1, 2, 3], vec![4, 5, 6], vec![0, 0, 0], vec![0, 0, 0]];
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        // 1st parity is the XOR of the data, 2nd adds twice the 2nd data shard.
+        let exp_res: [[u8; 3]; 4] = [[1, 2, 3], [4, 5, 6], [5, 7, 5], [9, 8, 15]];
+        for (row_index, row) in encoded_shard.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+
+        let broken_shards = vec![
+            vec![],
+            vec![],
+            encoded_shard[2].clone(),
+            encoded_shard[3].clone(),
+        ];
+        let decoded_shard = match rs.decode(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+
+        assert!(matches!(
+            ReedSolomon::with_custom_parity(2, vec![vec![0, 1], vec![1, 1]], gf8),
+            Err(Error::NotMds)
+        ));
+        assert!(matches!(
+            ReedSolomon::with_custom_parity(32, vec![vec![1; 32]; 32], gf8),
+            Err(Error::ConfigTooExpensive(_, _))
+        ));
+        assert!(matches!(
+            ReedSolomon::with_custom_parity(2, vec![vec![1, 1], vec![1]], gf8),
+            Err(Error::InconsistentRowLengths)
+        ));
+    }
+    #[test]
+    fn test_encode_cancellable() {
+        let result = ReedSolomon::new(10, 4);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards: Vec<Vec<u8>> = (0..14).map(|i| vec![i as u8; 1 << 16]).collect();
+
+        let should_cancel = AtomicBool::new(true);
+        assert!(matches!(
+            rs.encode_cancellable(shards.clone(), &should_cancel),
+            Err(Error::Cancelled)
+        ));
+
+        should_cancel.store(false, Ordering::Relaxed);
+        let encoded_shard = match rs.encode_cancellable(shards.clone(), &should_cancel) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let exp_res = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(exp_res, encoded_shard);
+    }
+    #[test]
+    fn test_shard_hashes() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![vec![0, 1, 2], vec![0, 1, 2], vec![0, 1, 3], vec![]];
+        let hashes = rs.shard_hashes(&shards);
+
+        assert_eq!(4, hashes.len());
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        assert_ne!(ReedSolomon::MISSING_SHARD_HASH, hashes[0]);
+        assert_eq!(ReedSolomon::MISSING_SHARD_HASH, hashes[3]);
+    }
+    #[test]
+    fn test_extreme_configurations() {
+        for &(data_shards, parity_shards) in [(255, 1), (1, 255), (128, 128)].iter() {
+            let result = ReedSolomon::new(data_shards, parity_shards);
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let total_shards = data_shards + parity_shards;
+            let shards: Vec<Vec<u8>> = (0..total_shards)
+                .map(|i| vec![i as u8, (i * 7) as u8, 255 - i as u8])
+                .collect();
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            for &missing in [0, data_shards - 1, data_shards, total_shards - 1].iter() {
+                let mut broken_shards = encoded_shard.clone();
+                broken_shards[missing] = vec![];
+                let decoded_shard = match rs.decode(broken_shards) {
+                    Ok(x) => x,
+                    Err(e) => panic!("{}", e),
+                };
+                assert_eq!(encoded_shard, decoded_shard);
+            }
+        }
+    }
+    #[test]
+    fn test_decode_options() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![200, 201, 203],
+            vec![100, 101, 102],
+        ];
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let broken_shards = vec![
+            None,
+            Some(encoded_shard[1].clone()),
+            None,
+            Some(encoded_shard[3].clone()),
+        ];
+        let decoded_shard = match rs.decode_options(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+
+        assert!(rs
+            .decode_options(vec![None, None, None, Some(encoded_shard[3].clone())])
+            .is_err());
+    }
+    #[test]
+    fn test_encode_from_chunks() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let chunks = (0..4).map(|i| vec![i, i + 10, i + 20]);
+        let encoded_shard = match rs.encode_from_chunks(chunks) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards: Vec<Vec<u8>> = (0..4).map(|i| vec![i, i + 10, i + 20]).collect();
+        shards.extend(vec![vec![0, 0, 0]; 2]);
+        let exp_res = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(exp_res, encoded_shard);
+
+        // Only the first 4 chunks are taken.
+        let mut chunks = (0..6).map(|i| vec![i, i + 10, i + 20]);
+        match rs.encode_from_chunks(chunks.by_ref()) {
+            Ok(x) => assert_eq!(exp_res, x),
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(Some(vec![4, 14, 24]), chunks.next());
+
+        assert!(matches!(
+            rs.encode_from_chunks((0..3).map(|i| vec![i])),
+            Err(Error::TooFewShards)
+        ));
+        assert!(matches!(
+            rs.encode_from_chunks((0..4).map(|i| vec![0; i + 1])),
+            Err(Error::InconsistentShards)
+        ));
+    }
+    #[test]
+    fn test_pad_shard() {
+        let mut shard = vec![1, 2, 3];
+        ReedSolomon::pad_shard(&mut shard, 8);
+        assert_eq!(8, shard.len());
+        assert_eq!([1, 2, 3], shard[..3]);
+        assert!(shard[3..].iter().all(|&b| b == 0));
+
+        ReedSolomon::pad_shard(&mut shard, 4);
+        assert_eq!(8, shard.len());
+    }
+    #[test]
+    fn test_encode_decode_chunked() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let pieces: Vec<Vec<u8>> = (0..7).map(|i| vec![i as u8; i * 3]).collect();
+        let encoded_shard = match rs.encode_chunked(&pieces) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(6, encoded_shard.len());
+
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[1] = vec![];
+        broken_shards[4] = vec![];
+        let decoded_pieces = match rs.decode_chunked(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(pieces, decoded_pieces);
+
+        let mut bad_table = encoded_shard;
+        bad_table[0][..8].copy_from_slice(&[255; 8]);
+        assert!(matches!(
+            rs.decode_chunked(bad_table),
+            Err(Error::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn test_debug_dump() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let dump = rs.debug_dump();
+        assert!(dump.contains("data_shards: 2\nparity_shards: 2\n"));
+        assert!(dump.contains("polynomial: 0x11d\n"));
+        for row in rs.matrix.data.iter() {
+            let elems: Vec<String> = row.iter().map(|elem| format!("{:02x}", elem)).collect();
+            assert!(dump.contains(&elems.join(" ")));
+        }
+        assert!(dump.contains("matrix:\n01 00\n00 01\n"));
+    }
+
+    #[test]
+    fn test_join_trim_trailing_zeros() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<u8> = vec![1, 2, 3, 4, 5, 6, 7];
+        let encoded_shard = match rs.encode(rs.split_payload(&data)) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[2] = vec![];
+        assert!(rs.join_trim_trailing_zeros(&broken_shards).is_empty());
+        let decoded_shard = match rs.decode(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(data, rs.join_trim_trailing_zeros(&decoded_shard));
+    }
+
+    #[test]
+    fn test_join_trim_trailing_zeros_over_trims() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        // Zeros at the end of the data can't be told apart from padding.
+        let data: Vec<u8> = vec![1, 2, 3, 4, 0, 0];
+        let encoded_shard = match rs.encode(rs.split_payload(&data)) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(
+            vec![1, 2, 3, 4],
+            rs.join_trim_trailing_zeros(&encoded_shard)
+        );
+    }
+
+    #[test]
+    fn test_quick_checksum() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![
+            vec![0, 1, 2, 3],
+            vec![4, 5, 6, 7],
+            vec![8, 9, 10, 11],
+            vec![0; 4],
+            vec![0; 4],
+        ];
+        let (encoded_shard, checksum) = match rs.encode_with_checksum(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(checksum, rs.quick_checksum(&encoded_shard));
+
+        for i in 0..encoded_shard.len() {
+            for j in 0..encoded_shard[i].len() {
+                let mut corrupted = encoded_shard.clone();
+                corrupted[i][j] ^= 0x10;
+                assert_ne!(checksum, rs.quick_checksum(&corrupted));
+            }
+        }
+
+        let mut swapped = encoded_shard;
+        swapped.swap(0, 1);
+        assert_ne!(checksum, rs.quick_checksum(&swapped));
+
+        let result = ReedSolomon::new(70, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards: Vec<Vec<u8>> = (0..70u8).map(|i| vec![i, i ^ 0x5A, 3]).collect();
+        shards.resize(72, vec![0; 3]);
+        let (encoded_shard, checksum) = match rs.encode_with_checksum(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for (i, j) in [(1, 65), (0, 64), (2, 71)] {
+            let mut swapped = encoded_shard.clone();
+            swapped.swap(i, j);
+            assert_ne!(checksum, rs.quick_checksum(&swapped));
+        }
+    }
+
+    #[test]
+    fn test_decode_streaming() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let data = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            let mut shards = data.clone();
+            shards.resize(5, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut broken_shards = encoded_shard;
+            broken_shards[1] = vec![];
+            broken_shards[3] = vec![];
+            let mut calls: Vec<(usize, Vec<u8>)> = vec![];
+            let result =
+                rs.decode_streaming(broken_shards, |i, shard| calls.push((i, shard.to_vec())));
+            if let Err(e) = result {
+                panic!("{}", e);
+            }
+            let expected: Vec<(usize, Vec<u8>)> = data.into_iter().enumerate().collect();
+            assert_eq!(expected, calls);
+        }
+    }
+
+    #[test]
+    fn test_critical_shards() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let all = [true; 6];
+        assert_eq!(2, rs.tolerable_losses(&all));
+        assert!(rs.critical_shards(&all).is_empty());
+
+        let one_lost = [true, false, true, true, true, true];
+        assert_eq!(1, rs.tolerable_losses(&one_lost));
+        assert!(rs.critical_shards(&one_lost).is_empty());
+
+        let two_lost = [true, false, true, true, false, true];
+        assert_eq!(0, rs.tolerable_losses(&two_lost));
+        assert_eq!(vec![0, 2, 3, 5], rs.critical_shards(&two_lost));
+
+        let parity_lost = [true, true, true, true, false, false];
+        assert_eq!(vec![0, 1, 2, 3], rs.critical_shards(&parity_lost));
+
+        let three_lost = [false, false, true, true, false, true];
+        assert_eq!(0, rs.tolerable_losses(&three_lost));
+        assert!(rs.critical_shards(&three_lost).is_empty());
+
+        assert_eq!(0, rs.tolerable_losses(&[true; 5]));
+        assert!(rs.critical_shards(&[true; 4]).is_empty());
+    }
+
+    #[test]
+    fn test_encode_budgeted() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let data: Vec<u8> = (0..100).map(|x| x as u8).collect();
+            let expected = match rs.encode(rs.split_payload(&data)) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            // Large enough for the whole set of shards.
+            let whole = match rs.encode_budgeted(&data, 1 << 20) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(expected, whole);
+
+            // Stripes of 3 columns, the last one being shorter, and of 1 column.
+            for max_memory in [15, 0] {
+                let streamed = match rs.encode_budgeted(&data, max_memory) {
+                    Ok(x) => x,
+                    Err(e) => panic!("{}", e),
+                };
+                assert_eq!(expected, streamed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_with_layout() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        let mut shards = data.clone();
+        shards.resize(5, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let layout = [
+            ShardRole::Parity(1),
+            ShardRole::Data(2),
+            ShardRole::Data(0),
+            ShardRole::Parity(0),
+            ShardRole::Data(1),
+        ];
+        let laid_out = match rs.encode_with_layout(&data, &layout) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard[4], laid_out[0]);
+        assert_eq!(data[2], laid_out[1]);
+        assert_eq!(data[0], laid_out[2]);
+        assert_eq!(encoded_shard[3], laid_out[3]);
+        assert_eq!(data[1], laid_out[4]);
+
+        let duplicated = [
+            ShardRole::Parity(1),
+            ShardRole::Data(2),
+            ShardRole::Data(0),
+            ShardRole::Parity(1),
+            ShardRole::Data(1),
+        ];
+        assert!(matches!(
+            rs.encode_with_layout(&data, &duplicated),
+            Err(Error::InvalidLayout)
+        ));
+        assert!(matches!(
+            rs.encode_with_layout(&data, &layout[..4]),
+            Err(Error::InvalidLayout)
+        ));
+        let out_of_bounds = [
+            ShardRole::Parity(2),
+            ShardRole::Data(2),
+            ShardRole::Data(0),
+            ShardRole::Parity(0),
+            ShardRole::Data(1),
+        ];
+        assert!(matches!(
+            rs.encode_with_layout(&data, &out_of_bounds),
+            Err(Error::InvalidLayout)
+        ));
+    }
+
+    #[test]
+    fn test_encode_decode_dir() {
+        let result = ReedSolomon::new(4, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let dir = std::env::temp_dir().join(format!("reed_solomon_dir_{}", std::process::id()));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            panic!("{}", e);
+        }
+
+        let data: Vec<u8> = (0..1000).map(|x| (x % 251) as u8).collect();
+        if let Err(e) = rs.encode_to_dir(&data, &dir, "object") {
+            panic!("{}", e);
+        }
+        for i in [1, 4] {
+            if let Err(e) = fs::remove_file(dir.join(format!("object.{}", i))) {
+                panic!("{}", e);
+            }
+        }
+        let restored = match rs.decode_from_dir(&dir, "object") {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(data, restored);
+
+        let other = match ReedSolomon::new(3, 3) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(matches!(
+            other.decode_from_dir(&dir, "object"),
+            Err(Error::InvalidHeader)
+        ));
+        assert!(matches!(
+            rs.decode_from_dir(&dir, "missing"),
+            Err(Error::Io(_))
+        ));
+
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            panic!("{}", e);
+        }
+    }
+
+    #[test]
+    fn test_parity_check_matrix() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(4, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let parity_check = match rs.parity_check_matrix() {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(2, parity_check.data.len());
+            assert_eq!(6, parity_check.data[0].len());
+
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+            shards.resize(6, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let syndrome = |shards: &[Vec<u8>], pos: usize| {
+                let codeword = Matrix::new_from_data(shards.iter().map(|x| vec![x[pos]]).collect());
+                match parity_check.mul(codeword, &rs.gf) {
+                    Ok(x) => x.into_data(),
+                    Err(e) => panic!("{}", e),
+                }
+            };
+            for pos in 0..3 {
+                assert_eq!(vec![vec![0], vec![0]], syndrome(&encoded_shard, pos));
+            }
+
+            let mut corrupted = encoded_shard;
+            corrupted[2][1] ^= 0x55;
+            assert_ne!(vec![vec![0], vec![0]], syndrome(&corrupted, 1));
+        }
+    }
+
+    #[test]
+    fn test_encode_catch() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+        let expected = match rs.encode(shards.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let encoded_shard = match rs.encode_catch(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(expected, encoded_shard);
+        assert!(matches!(
+            rs.encode_catch(vec![vec![0, 1, 2]]),
+            Err(Error::WrongNoOfShards)
+        ));
+
+        let result: Result<(), Error> = ReedSolomon::catch_panic(|| panic!("synthetic panic"));
+        match result {
+            Err(Error::Internal(message)) => assert_eq!("synthetic panic", message),
+            _ => panic!("the panic was not converted"),
+        }
+    }
+
+    #[test]
+    fn test_compatible_with() {
+        let new = |data_shards, parity_shards, systematic| match ReedSolomonBuilder::new(
+            data_shards,
+            parity_shards,
+        )
+        .systematic(systematic)
+        .build()
+        {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let rs = new(4, 2, true);
+        assert!(rs.compatible_with(&rs));
+        assert!(rs.compatible_with(&new(4, 3, true)));
+        assert!(new(4, 3, true).compatible_with(&rs));
+        assert!(!rs.compatible_with(&new(5, 2, true)));
+        assert!(!rs.compatible_with(&new(4, 2, false)));
+        assert!(new(4, 2, false).compatible_with(&new(4, 3, false)));
+    }
+
+    #[test]
+    fn test_reconstruct_cheapest() {
+        let result = ReedSolomon::new(10, 4);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards: Vec<Vec<u8>> = (0..10).map(|i| vec![i as u8, 2 * i as u8, 3]).collect();
+        shards.resize(14, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        for missing in [vec![10, 12, 13], vec![0, 4, 11], vec![1, 2, 3, 9]] {
+            let mut broken_shards = encoded_shard.clone();
+            for &i in missing.iter() {
+                broken_shards[i] = vec![];
+            }
+            let decoded_shard = match rs.decode(broken_shards.clone()) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let reconstructed = match rs.reconstruct_cheapest(broken_shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(decoded_shard, reconstructed);
+            assert_eq!(encoded_shard, reconstructed);
+        }
+    }
+
+    #[test]
+    fn test_decode_exactly_data_shard_count_present() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+            shards.resize(5, vec![0; 4]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            // Every way of losing 2 of the 5 shards leaves exactly 3.
+            for first in 0..5 {
+                for second in first + 1..5 {
+                    let mut broken_shards = encoded_shard.clone();
+                    broken_shards[first] = vec![];
+                    broken_shards[second] = vec![];
+                    let decoded_shard = match rs.decode(broken_shards) {
+                        Ok(x) => x,
+                        Err(e) => panic!("{}", e),
+                    };
+                    assert_eq!(encoded_shard, decoded_shard);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_present_indices() {
+        let result = ReedSolomon::new(2, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+        assert_eq!(vec![0, 2], rs.present_indices(&shards));
+        assert!(rs.present_indices(&[vec![], vec![]]).is_empty());
+    }
+
+    #[test]
+    fn test_add_parity_shard() {
+        for (matrix_kind, systematic) in [
+            (MatrixKind::Vandermonde, true),
+            (MatrixKind::Vandermonde, false),
+            (MatrixKind::Cauchy, true),
+            (MatrixKind::Cauchy, false),
+        ] {
+            let new = |parity_shards| match ReedSolomonBuilder::new(4, parity_shards)
+                .matrix_kind(matrix_kind)
+                .systematic(systematic)
+                .build()
+            {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let rs = new(2);
+            let more = new(3);
+
+            let data = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+            let mut shards = data.clone();
+            shards.resize(6, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let new_parity = match more.add_parity_shard(&encoded_shard) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut shards = data;
+            shards.resize(7, vec![0; 3]);
+            let expected = match more.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut extended = encoded_shard;
+            extended.push(new_parity);
+            assert_eq!(expected, extended);
+
+            // Any 4 shards of the extended set are enough.
+            let mut broken_shards = extended.clone();
+            broken_shards[0] = vec![];
+            broken_shards[1] = vec![];
+            broken_shards[4] = vec![];
+            let decoded_shard = match more.decode(broken_shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(extended, decoded_shard);
+
+            assert!(more.add_parity_shard(&extended).is_err());
+        }
+    }
+
+    #[test]
+    fn test_new_checked() {
+        assert!(matches!(
+            ReedSolomon::new_checked(200, 50, ReedSolomon::EXPENSIVE_INVERT_OPS),
+            Err(Error::ConfigTooExpensive(
+                8_000_000,
+                ReedSolomon::EXPENSIVE_INVERT_OPS
+            ))
+        ));
+        assert!(ReedSolomon::new_checked(200, 50, 8_000_000).is_ok());
+        assert!(ReedSolomon::new_checked(10, 4, ReedSolomon::EXPENSIVE_INVERT_OPS).is_ok());
+        assert!(matches!(
+            ReedSolomon::new_checked(0, 4, ReedSolomon::EXPENSIVE_INVERT_OPS),
+            Err(Error::ZeroDataShards)
+        ));
+    }
+
+    #[test]
+    fn test_encode_decode_with_trailer() {
+        // 8 bytes and their length fill 3 data shards without padding, and
+        // 200 data shards of 1 byte hold the 2 byte length of 128 bytes
+        // across the last 2 data shards.
+        for (data_shards, data_len) in [(3, 10), (3, 8), (4, 200), (2, 0), (200, 128)] {
+            let result = ReedSolomon::new(data_shards, 2);
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let data: Vec<u8> = (0..data_len).map(|x| (x % 256) as u8).collect();
+            let encoded_shard = match rs.encode_with_trailer(&data) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(data_shards + 2, encoded_shard.len());
+
+            let mut broken_shards = encoded_shard;
+            broken_shards[data_shards - 1] = vec![];
+            broken_shards[0] = vec![];
+            let decoded_data = match rs.decode_with_trailer(broken_shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(data, decoded_data);
+        }
+    }
+
+    #[test]
+    fn test_flat_encoded_size() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for data_len in [0, 1, 2, 3, 4, 100, 1000] {
+            let data: Vec<u8> = (0..data_len).map(|x| (x % 256) as u8).collect();
+            let flat = match rs.encode_to_flat(&data) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(rs.flat_encoded_size(data_len), flat.len());
+            assert_eq!(data[..], flat[..data_len]);
+        }
+    }
+
+    #[test]
+    fn test_decode_lenient() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(5, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[1] = vec![];
+        broken_shards.push(vec![]);
+        assert!(matches!(
+            rs.decode(broken_shards.clone()),
+            Err(Error::TooManyShards)
+        ));
+        let decoded_shard = match rs.decode_lenient(broken_shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+
+        assert!(matches!(
+            rs.decode_lenient(encoded_shard[..4].to_vec()),
+            Err(Error::TooFewShards)
+        ));
+    }
+
+    #[test]
+    fn test_encode_decode_random() {
+        // Every case has its own seed, so a failing case can be reproduced
+        // from the seed in the message alone.
+        for seed in 0..100u64 {
+            let mut state = seed;
+            let mut next = |bound: usize| (whiten::split_mix64(&mut state) % bound as u64) as usize;
+
+            let data_shards = 1 + next(64);
+            let parity_shards = 1 + next(32);
+            let shard_elem_len = 1 + next(64);
+            let systematic = next(2) == 0;
+            let total_shards = data_shards + parity_shards;
+
+            let result = ReedSolomonBuilder::new(data_shards, parity_shards)
+                .systematic(systematic)
+                .build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("seed {}: {}", seed, e),
+            };
+            let mut shards: Vec<Vec<u8>> = (0..total_shards)
+                .map(|_| (0..shard_elem_len).map(|_| next(256) as u8).collect())
+                .collect();
+            for shard in shards[data_shards..].iter_mut() {
+                shard.fill(0);
+            }
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("seed {}: {}", seed, e),
+            };
+
+            // Erase a random subset of at most parity shards count shards.
+            let mut indices: Vec<usize> = (0..total_shards).collect();
+            for i in (1..total_shards).rev() {
+                indices.swap(i, next(i + 1));
+            }
+            let erased = next(parity_shards + 1);
+            let mut broken_shards = encoded_shard.clone();
+            for &i in indices[..erased].iter() {
+                broken_shards[i] = vec![];
+            }
+
+            let decoded_shard = match rs.decode(broken_shards) {
+                Ok(x) => x,
+                Err(e) => panic!("seed {}: {}", seed, e),
+            };
+            assert!(
+                encoded_shard == decoded_shard,
+                "seed {}: {}+{} with {} byte shards, erased {:?}",
+                seed,
+                data_shards,
+                parity_shards,
+                shard_elem_len,
+                &indices[..erased]
+            );
+        }
+    }
+
+    #[test]
+    fn test_recover_single() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(4, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9, 10, 11]];
+            shards.resize(6, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            for missing in 0..6 {
+                let mut broken_shards = encoded_shard.clone();
+                broken_shards[missing] = vec![];
+                let decoded_shard = match rs.decode(broken_shards.clone()) {
+                    Ok(x) => x,
+                    Err(e) => panic!("{}", e),
+                };
+                if let Err(e) = rs.recover_single(&mut broken_shards, missing) {
+                    panic!("{}", e);
+                }
+                assert_eq!(decoded_shard, broken_shards);
+                assert_eq!(encoded_shard, broken_shards);
+            }
+
+            let mut broken_shards = encoded_shard.clone();
+            broken_shards[0] = vec![];
+            broken_shards[1] = vec![];
+            assert!(matches!(
+                rs.recover_single(&mut broken_shards, 0),
+                Err(Error::TooFewShards)
+            ));
+            assert!(matches!(
+                rs.recover_single(&mut broken_shards, 6),
+                Err(Error::InvalidShardIndex(6))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_encode_in_place() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(5, vec![0; 3]);
+            let expected = match rs.encode(shards.clone()) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let data_ptr = shards[0].as_ptr();
+            let parity_ptr = shards[4].as_ptr();
+            if let Err(e) = rs.encode_in_place(&mut shards) {
+                panic!("{}", e);
+            }
+            assert_eq!(expected, shards);
+            assert_eq!(data_ptr, shards[0].as_ptr());
+            assert_eq!(parity_ptr, shards[4].as_ptr());
+
+            assert!(matches!(
+                rs.encode_in_place(&mut shards[..4]),
+                Err(Error::WrongNoOfShards)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_encode_sep() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(5, vec![0; 3]);
+        let expected = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        // Every shard lives in one buffer, as if it was mapped from a file.
+        let mut buffer = [0u8; 15];
+        buffer[..9].copy_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let (data_buffer, parity_buffer) = buffer.split_at_mut(9);
+        let data: Vec<&[u8]> = data_buffer.chunks(3).collect();
+        let mut parity: Vec<&mut [u8]> = parity_buffer.chunks_mut(3).collect();
+        if let Err(e) = rs.encode_sep(&data, &mut parity) {
+            panic!("{}", e);
+        }
+        assert_eq!(expected.concat(), buffer);
+
+        let data: Vec<&[u8]> = vec![&[0, 1, 2], &[3, 4, 5], &[6, 7]];
+        let mut parity_buffer = [0u8; 6];
+        let mut parity: Vec<&mut [u8]> = parity_buffer.chunks_mut(3).collect();
+        assert!(matches!(
+            rs.encode_sep(&data, &mut parity),
+            Err(Error::InconsistentShards)
+        ));
+
+        let result = ReedSolomonBuilder::new(3, 2).systematic(false).build();
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(matches!(
+            rs.encode_sep(&data, &mut parity),
+            Err(Error::NotSystematic)
+        ));
+    }
+
+    #[test]
+    fn test_verify() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(5, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            match rs.verify(&encoded_shard) {
+                Ok(x) => assert!(x),
+                Err(e) => panic!("{}", e),
+            }
+
+            for i in 0..5 {
+                let mut corrupted = encoded_shard.clone();
+                corrupted[i][1] ^= 1;
+                match rs.verify(&corrupted) {
+                    Ok(x) => assert!(!x),
+                    Err(e) => panic!("{}", e),
+                }
+            }
+
+            assert!(matches!(
+                rs.verify(&encoded_shard[..4]),
+                Err(Error::WrongNoOfShards)
+            ));
+        }
+    }
+    #[test]
+    fn test_reconstruct() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 3).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(6, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut broken_shards: Vec<Option<Vec<u8>>> =
+                encoded_shard.iter().cloned().map(Some).collect();
+            broken_shards[0] = None;
+            broken_shards[2] = None;
+            broken_shards[4] = None;
+            if let Err(e) = rs.reconstruct(&mut broken_shards) {
+                panic!("{}", e);
+            }
+            let expected: Vec<Option<Vec<u8>>> = encoded_shard.iter().cloned().map(Some).collect();
+            assert_eq!(expected, broken_shards);
+
+            // A present shard can't be empty.
+            broken_shards[1] = Some(vec![]);
+            broken_shards[3] = None;
+            assert!(matches!(
+                rs.reconstruct(&mut broken_shards),
+                Err(Error::InconsistentShards)
+            ));
+            broken_shards = vec![None; 6];
+            assert!(matches!(
+                rs.reconstruct(&mut broken_shards),
+                Err(Error::TooFewShards)
+            ));
+        }
+    }
+    #[test]
+    fn test_decode_with_erasures() {
+        let result = ReedSolomon::new(3, 2);
+        let rs = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(5, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        // The lost shards hold stale bytes, or nothing at all.
+        let mut broken_shards = encoded_shard.clone();
+        broken_shards[0] = vec![9, 9, 9];
+        broken_shards[3] = vec![];
+        let decoded_shard = match rs.decode_with_erasures(broken_shards.clone(), &[0, 3]) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded_shard, decoded_shard);
+
+        assert!(matches!(
+            rs.decode_with_erasures(broken_shards.clone(), &[0, 5]),
+            Err(Error::InvalidShardIndex(5))
+        ));
+        assert!(matches!(
+            rs.decode_with_erasures(broken_shards, &[0, 1, 3]),
+            Err(Error::TooFewShards)
+        ));
+    }
+    #[test]
+    fn test_reconstruct_data() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 3).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(6, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut broken_shards: Vec<Option<Vec<u8>>> =
+                encoded_shard.iter().cloned().map(Some).collect();
+            broken_shards[1] = None;
+            broken_shards[2] = None;
+            broken_shards[5] = None;
+            if let Err(e) = rs.reconstruct_data(&mut broken_shards) {
+                panic!("{}", e);
+            }
+            for i in 0..5 {
+                assert_eq!(Some(encoded_shard[i].clone()), broken_shards[i]);
+            }
+            assert_eq!(None, broken_shards[5]);
 
-        if present == self.total_shard_count {
-            // All of the shards have data so we can return
-            return Ok(shards);
+            // Nothing to do when only parity shards are missing.
+            if let Err(e) = rs.reconstruct_data(&mut broken_shards) {
+                panic!("{}", e);
+            }
+            assert_eq!(None, broken_shards[5]);
         }
+    }
+    #[test]
+    fn test_reconstruct_shards() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 3).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(6, vec![0; 3]);
+            let encoded_shard = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
 
-        // Pull out the rows of the matrix that correspond
-        // to the given shards and build a square matrix.
-        // This matrix could be used to generate the shards
-        // that we have from the original data.
-        //
-        // Create an array holding just the shards that
-        // correspond to the rows of the submatrix. These
-        // shards will be the input to the decoding process
-        // that re-creates the missing data shards.
-        let mut sub_matrix = Matrix::new(self.data_shard_count, self.data_shard_count);
-        let mut sub_shard: Vec<Vec<u8>> = vec![vec![]; self.data_shard_count];
-        let mut sub_matrix_row: usize = 0;
-        let mut matrix_row: usize = 0;
-        while matrix_row < self.total_shard_count && sub_matrix_row < self.data_shard_count {
-            if shards[matrix_row].len() != 0 {
-                sub_matrix.data[sub_matrix_row] = self.matrix.data[matrix_row].clone();
-                sub_shard[sub_matrix_row] = shards[matrix_row].clone();
-                sub_matrix_row += 1;
-            }
-            matrix_row += 1;
-        }
-        // Invert the matrix, so we can go from the encoded shards
-        // back to the original data. Then pull out the row that
-        // generates the shard that we want to decode. Since this
-        // matrix maps back to the orginal data, it can be used
-        // to create a data shard, but not a parity shard.
-        let data_decode_matrix = sub_matrix.invert(self.gf)?;
-
-        // Re-create any data shards that were missing.
-        //
-        // The input to the coding is all of the shards we actually
-        // have, and the output is the missing data shards. The computation
-        // is done using the special decode matrix we just built.
-        let mut matrix_rows = Matrix::new(self.parity_shard_count, self.data_shard_count);
-        let mut outputs: Vec<Vec<u8>> = vec![vec![0; shard_elem_len]; self.parity_shard_count];
-        let mut output_count: usize = 0;
-        for i in 0..self.data_shard_count {
-            if shards[i].len() == 0 {
-                matrix_rows.data[output_count] = data_decode_matrix.data[i].clone();
-                output_count += 1;
+            let mut broken_shards: Vec<Option<Vec<u8>>> =
+                encoded_shard.iter().cloned().map(Some).collect();
+            broken_shards[0] = None;
+            broken_shards[2] = None;
+            broken_shards[4] = None;
+            if let Err(e) = rs.reconstruct_shards(&mut broken_shards, &[4, 2, 1, 4]) {
+                panic!("{}", e);
             }
+            assert_eq!(None, broken_shards[0]);
+            assert_eq!(Some(encoded_shard[2].clone()), broken_shards[2]);
+            assert_eq!(Some(encoded_shard[4].clone()), broken_shards[4]);
+
+            assert!(matches!(
+                rs.reconstruct_shards(&mut broken_shards, &[6]),
+                Err(Error::InvalidShardIndex(6))
+            ));
         }
-        self.encode_shards(&matrix_rows, &sub_shard, &mut outputs);
+    }
+    #[test]
+    fn test_reconstruct_parity_only() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(5, vec![0; 3]);
+        let encoded_shard = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
 
-        // Filling the missing data shards.
-        output_count = 0;
-        let mut shards = shards;
-        for i in 0..self.data_shard_count {
-            if shards[i].len() == 0 {
-                shards[i] = outputs[output_count].clone();
-                output_count += 1;
+        let mut broken_shards: Vec<Option<Vec<u8>>> =
+            encoded_shard.iter().cloned().map(Some).collect();
+        broken_shards[3] = None;
+        broken_shards[4] = None;
+        if let Err(e) = rs.reconstruct(&mut broken_shards) {
+            panic!("{}", e);
+        }
+        assert_eq!(
+            encoded_shard,
+            broken_shards.into_iter().flatten().collect::<Vec<_>>()
+        );
+
+        // The parity shards are encoded again, so nothing was inverted.
+        let key = DecodeCache::key(&[0, 1, 2]);
+        let cached = match rs.decode_cache.lock() {
+            Ok(mut cache) => cache.get(&key),
+            Err(e) => panic!("{}", e),
+        };
+        assert!(cached.is_none());
+    }
+    #[test]
+    fn test_geometry() {
+        let rs = match ReedSolomon::new(5, 3) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(5, rs.data_shard_count());
+        assert_eq!(3, rs.parity_shard_count());
+        assert_eq!(8, rs.total_shard_count());
+        assert_eq!(
+            Geometry {
+                data_shards: 5,
+                parity_shards: 3,
+                total_shards: 8,
+            },
+            rs.geometry()
+        );
+    }
+    #[test]
+    fn test_clone_eq_debug() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let cloned = rs.clone();
+        assert_eq!(rs, cloned);
+        assert!(format!("{:?}", cloned).starts_with("ReedSolomon { data_shard_count: 3"));
+
+        let other = match ReedSolomon::new(3, 3) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_ne!(rs, other);
+        let non_systematic = match ReedSolomonBuilder::new(3, 2).systematic(false).build() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_ne!(rs, non_systematic);
+    }
+    #[test]
+    fn test_with_matrix() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let result = ReedSolomon::with_matrix(3, 2, rs.matrix.clone());
+        match result {
+            Ok(x) => assert_eq!(rs, x),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut not_mds = rs.matrix.clone();
+        not_mds.data[4] = not_mds.data[3].clone();
+        assert!(matches!(
+            ReedSolomon::with_matrix(3, 2, not_mds),
+            Err(Error::NotMds)
+        ));
+        let mut not_systematic = rs.matrix.clone();
+        not_systematic.data.swap(0, 1);
+        assert!(matches!(
+            ReedSolomon::with_matrix(3, 2, not_systematic),
+            Err(Error::NotSystematic)
+        ));
+        assert!(matches!(
+            ReedSolomon::with_matrix(3, 3, rs.matrix.clone()),
+            Err(Error::WrongNoOfShards)
+        ));
+        let mut ragged = rs.matrix.clone();
+        ragged.data[4].pop();
+        assert!(matches!(
+            ReedSolomon::with_matrix(3, 2, ragged),
+            Err(Error::InconsistentRowLengths)
+        ));
+    }
+    #[test]
+    fn test_split_join() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            for len in [0, 1, 3, 10] {
+                let data: Vec<u8> = (0..len).map(|x| x as u8 + 1).collect();
+                let shards = rs.split(&data);
+                assert_eq!(5, shards.len());
+                let encoded = match rs.encode(shards) {
+                    Ok(x) => x,
+                    Err(e) => panic!("{}", e),
+                };
+                match rs.join(&encoded, len) {
+                    Ok(x) => assert_eq!(data, x),
+                    Err(e) => panic!("{}", e),
+                }
             }
         }
 
-        // Filling missing parity shards with placeholder
-        for i in self.data_shard_count..self.total_shard_count {
-            if shards[i].len() == 0 {
-                shards[i] = vec![0; shard_elem_len]
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = rs.split(&[1, 2, 3, 4]);
+        assert!(matches!(
+            rs.join(&shards, 7),
+            Err(Error::DataLenOutOfBounds(7, 6))
+        ));
+    }
+    #[test]
+    fn test_encode_parity_shard() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let data = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            let mut shards = data.clone();
+            shards.resize(5, vec![0; 3]);
+            let encoded = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let refs: Vec<&[u8]> = data.iter().map(|shard| &shard[..]).collect();
+            for p in 0..2 {
+                match rs.encode_parity_shard(&refs, p) {
+                    Ok(x) => assert_eq!(encoded[3 + p], x),
+                    Err(e) => panic!("{}", e),
+                }
             }
+            assert!(matches!(
+                rs.encode_parity_shard(&refs, 2),
+                Err(Error::InvalidShardIndex(2))
+            ));
         }
-        // Now that we have all of the data shards intact, we can
-        // compute any of the parity that is missing.
-        //
-        // The input to the coding is ALL of the data shards, including
-        // any that we just calculated. The output is all parity shards.
-        self.encode(shards)
     }
-}
+    #[test]
+    fn test_update() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(5, vec![0; 3]);
+        let old = match rs.encode(shards.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        shards[1] = vec![30, 40, 50];
+        let new = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut parity = old[3..].to_vec();
+        if let Err(e) = rs.update(&old[1], &new[1], 1, &mut parity) {
+            panic!("{}", e);
+        }
+        assert_eq!(new[3..], parity[..]);
 
+        assert!(matches!(
+            rs.update(&old[1], &new[1], 3, &mut parity),
+            Err(Error::InvalidShardIndex(3))
+        ));
+        let result = ReedSolomonBuilder::new(3, 2).systematic(false).build();
+        let non_systematic = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(matches!(
+            non_systematic.update(&old[1], &new[1], 1, &mut parity),
+            Err(Error::NotSystematic)
+        ));
+    }
     #[test]
-    fn test_new() {
-        let result = ReedSolomon::new(4, 2);
+    fn test_decode_cache() {
+        let result = ReedSolomonBuilder::new(3, 2).decode_cache_size(1).build();
         let rs = match result {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let exp_res: [[u8; 4]; 6] = [
-            [1, 0, 0, 0],
-            [0, 1, 0, 0],
-            [0, 0, 1, 0],
-            [0, 0, 0, 1],
-            [27, 28, 18, 20],
-            [28, 27, 20, 18],
-        ];
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(5, vec![0; 3]);
+        let encoded = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
 
-        for (row_index, row) in rs.matrix.data.iter().enumerate() {
-            for (col_index, &elem) in row.iter().enumerate() {
-                assert_eq!(exp_res[row_index][col_index], elem);
-            }
-        }
-        for (row_index, row) in rs.parity.data.iter().enumerate() {
-            for (col_index, &elem) in row.iter().enumerate() {
-                assert_eq!(exp_res[rs.data_shard_count + row_index][col_index], elem);
+        let mut broken = encoded.clone();
+        broken[1] = vec![];
+        for _ in 0..2 {
+            match rs.decode(broken.clone()) {
+                Ok(x) => assert_eq!(encoded, x),
+                Err(e) => panic!("{}", e),
             }
         }
+        let key = DecodeCache::key(&[0, 2, 3]);
+        let cached = match rs.decode_cache.lock() {
+            Ok(mut cache) => cache.get(&key),
+            Err(e) => panic!("{}", e),
+        };
+        assert!(cached.is_some());
     }
     #[test]
-    fn test_encode() {
-        let result = ReedSolomon::new(2, 2);
-        let rs = match result {
+    fn test_verify_with_report() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(5, vec![0; 3]);
+            let encoded = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            match rs.verify_with_report(&encoded) {
+                Ok(x) => {
+                    assert!(x.is_consistent());
+                    assert_eq!(Some(vec![]), x.suspects);
+                }
+                Err(e) => panic!("{}", e),
+            }
+
+            for i in 0..5 {
+                let mut corrupted = encoded.clone();
+                corrupted[i][2] ^= 0x40;
+                match rs.verify_with_report(&corrupted) {
+                    Ok(x) => {
+                        assert!(!x.is_consistent());
+                        assert_eq!(Some(vec![i]), x.suspects);
+                    }
+                    Err(e) => panic!("{}", e),
+                }
+            }
+        }
+
+        let rs = match ReedSolomon::new(2, 1) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let shards = vec![
-            vec![0, 1, 2],
-            vec![3, 4, 5],
-            vec![200, 201, 203],
-            vec![100, 101, 102],
-        ];
-        let encoded_shard_result = rs.encode(shards);
-        let encoded_shard = match encoded_shard_result {
+        let mut encoded = match rs.encode(vec![vec![0, 1], vec![2, 3], vec![0; 2]]) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let exp_res: [[u8; 3]; 4] = [[0, 1, 2], [3, 4, 5], [6, 11, 12], [5, 14, 11]];
-        for (row_index, row) in encoded_shard.iter().enumerate() {
-            for (col_index, &elem) in row.iter().enumerate() {
-                assert_eq!(exp_res[row_index][col_index], elem);
+        encoded[0][0] ^= 1;
+        match rs.verify_with_report(&encoded) {
+            Ok(x) => {
+                assert_eq!(vec![0], x.mismatched_parity);
+                assert_eq!(None, x.suspects);
             }
+            Err(e) => panic!("{}", e),
         }
     }
     #[test]
-    fn test_decode_missing_data() {
-        let result = ReedSolomon::new(2, 2);
+    fn test_correct() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 4).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards: Vec<Vec<u8>> = (0..3)
+                .map(|i| (0..8).map(|j| (i * 41 + j * 7) as u8).collect())
+                .collect();
+            shards.resize(7, vec![0; 8]);
+            let encoded = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            // 2 errors per position, but 3 corrupted shards in total.
+            let mut corrupted = encoded.clone();
+            corrupted[0][1] ^= 0x11;
+            corrupted[4][1] ^= 0x22;
+            corrupted[4][5] ^= 0x33;
+            corrupted[6][5] ^= 0x44;
+            match rs.correct(&mut corrupted) {
+                Ok(x) => assert_eq!(vec![0, 4, 6], x),
+                Err(e) => panic!("{}", e),
+            }
+            assert_eq!(encoded, corrupted);
+
+            let mut intact = encoded.clone();
+            match rs.correct(&mut intact) {
+                Ok(x) => assert!(x.is_empty()),
+                Err(e) => panic!("{}", e),
+            }
+
+            // Position 1 could be corrected, but position 5 can't, so
+            // nothing is written.
+            let mut corrupted = encoded.clone();
+            corrupted[0][1] ^= 0x11;
+            corrupted[0][5] ^= 0x22;
+            corrupted[3][5] ^= 0x33;
+            corrupted[6][5] ^= 0x44;
+            let expected = corrupted.clone();
+            assert!(matches!(
+                rs.correct(&mut corrupted),
+                Err(Error::TooManyErrors)
+            ));
+            assert_eq!(expected, corrupted);
+        }
+
+        let result = ReedSolomonBuilder::new(3, 4)
+            .matrix_kind(MatrixKind::Cauchy)
+            .build();
         let rs = match result {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let shards = vec![
-            vec![0, 1, 2],
-            vec![3, 4, 5],
-            vec![200, 201, 203],
-            vec![100, 101, 102],
-        ];
-        let encoded_shard_result = rs.encode(shards.clone());
-        let encoded_shard = match encoded_shard_result {
+        let mut shards = vec![vec![0; 2]; 7];
+        assert!(matches!(
+            rs.correct(&mut shards),
+            Err(Error::UnsupportedMatrix)
+        ));
+    }
+    #[test]
+    fn test_encode_into() {
+        let rs = match ReedSolomon::new(3, 2) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let broken_shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![5, 14, 11]];
-        let decoded_shard_result = rs.decode(broken_shards);
-        let decoded_shard = match decoded_shard_result {
+        let data = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        let mut shards = data.clone();
+        shards.resize(5, vec![0; 3]);
+        let encoded = match rs.encode(shards) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        for (row_index, row) in decoded_shard.iter().enumerate() {
-            for (col_index, &elem) in row.iter().enumerate() {
-                assert_eq!(encoded_shard[row_index][col_index], elem);
+
+        // Stale bytes in the buffers are overwritten.
+        let mut parity = vec![vec![0xFF; 3]; 2];
+        if let Err(e) = rs.encode_into(&data, &mut parity) {
+            panic!("{}", e);
+        }
+        assert_eq!(encoded[3..], parity[..]);
+
+        let mut short = vec![vec![0; 2]; 2];
+        assert!(matches!(
+            rs.encode_into(&data, &mut short),
+            Err(Error::InconsistentShards)
+        ));
+    }
+    #[test]
+    fn test_generic_shards() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards: Vec<Box<[u8]>> = vec![
+            Box::new([0, 1, 2]),
+            Box::new([3, 4, 5]),
+            Box::new([6, 7, 8]),
+            Box::new([0; 3]),
+            Box::new([0; 3]),
+        ];
+        if let Err(e) = rs.encode_in_place(&mut shards) {
+            panic!("{}", e);
+        }
+        let slices: Vec<&[u8]> = shards.iter().map(|shard| &shard[..]).collect();
+        match rs.verify(&slices) {
+            Ok(x) => assert!(x),
+            Err(e) => panic!("{}", e),
+        }
+        match rs.encode_parity_shard(&slices[..3], 1) {
+            Ok(x) => assert_eq!(slices[4], &x[..]),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut broken: Vec<Option<Box<[u8]>>> = shards.iter().cloned().map(Some).collect();
+        broken[0] = None;
+        broken[4] = None;
+        if let Err(e) = rs.reconstruct(&mut broken) {
+            panic!("{}", e);
+        }
+        let decoded: Vec<Box<[u8]>> = broken.into_iter().flatten().collect();
+        assert_eq!(shards, decoded);
+    }
+
+    #[test]
+    fn test_encode_batch() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut stripes: Vec<Stripe> = (0..10u8)
+                .map(|i| {
+                    let mut stripe: Stripe = (0..3).map(|j| vec![i, j, i ^ j, 7]).collect();
+                    stripe.resize(5, vec![0; 4]);
+                    stripe
+                })
+                .collect();
+            let expected: Vec<Stripe> = stripes
+                .iter()
+                .map(|stripe| match rs.encode(stripe.clone()) {
+                    Ok(x) => x,
+                    Err(e) => panic!("{}", e),
+                })
+                .collect();
+            if let Err(e) = rs.encode_batch(&mut stripes) {
+                panic!("{}", e);
             }
+            assert_eq!(expected, stripes);
         }
     }
+
     #[test]
-    fn test_decode_missing_parity() {
-        let result = ReedSolomon::new(2, 2);
-        let rs = match result {
+    fn test_encode_batch_checks_every_stripe() {
+        let rs = match ReedSolomon::new(2, 2) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let shards = vec![
+        let first = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+        let mut stripes = vec![first.clone(), vec![vec![0, 1, 2], vec![3, 4, 5]]];
+        assert!(matches!(
+            rs.encode_batch(&mut stripes),
+            Err(Error::WrongNoOfShards)
+        ));
+        assert_eq!(first, stripes[0]);
+    }
+
+    #[test]
+    fn test_decode_indexed() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let encoded = match rs.encode(vec![
             vec![0, 1, 2],
             vec![3, 4, 5],
-            vec![200, 201, 203],
-            vec![100, 101, 102],
+            vec![6, 7, 8],
+            vec![0; 3],
+            vec![0; 3],
+        ]) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let shards: Vec<(usize, &[u8])> = [4, 1, 3].iter().map(|&i| (i, &encoded[i][..])).collect();
+        match rs.decode_indexed(&shards) {
+            Ok(x) => assert_eq!(encoded, x),
+            Err(e) => panic!("{}", e),
+        }
+
+        let duplicated = [
+            (4, &encoded[4][..]),
+            (1, &encoded[1][..]),
+            (4, &encoded[4][..]),
         ];
-        let encoded_shard_result = rs.encode(shards.clone());
-        let encoded_shard = match encoded_shard_result {
+        assert!(matches!(
+            rs.decode_indexed(&duplicated),
+            Err(Error::DuplicateShardIndex(4))
+        ));
+        let out_of_bounds = [(5, &encoded[4][..])];
+        assert!(matches!(
+            rs.decode_indexed(&out_of_bounds),
+            Err(Error::InvalidShardIndex(5))
+        ));
+        assert!(matches!(
+            rs.decode_indexed(&shards[..2]),
+            Err(Error::TooFewShards)
+        ));
+    }
+
+    #[test]
+    fn test_decode_data_indexed() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(5, vec![0; 3]);
+            let encoded = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            for indices in [[0, 1, 2], [4, 0, 3], [2, 4, 1]] {
+                let subset: Vec<(usize, &[u8])> =
+                    indices.iter().map(|&i| (i, &encoded[i][..])).collect();
+                match rs.decode_data_indexed(&subset) {
+                    Ok(x) => assert_eq!(encoded[..3], x[..]),
+                    Err(e) => panic!("{}", e),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_checked() {
+        let rs = match ReedSolomon::new(3, 3) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let broken_shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 11, 12], vec![]];
-        let decoded_shard_result = rs.decode(broken_shards);
-        let decoded_shard = match decoded_shard_result {
+        let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        shards.resize(6, vec![0; 3]);
+        let encoded = match rs.encode(shards) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        for (row_index, row) in decoded_shard.iter().enumerate() {
-            for (col_index, &elem) in row.iter().enumerate() {
-                assert_eq!(encoded_shard[row_index][col_index], elem);
-            }
+
+        let mut broken = encoded.clone();
+        broken[1] = vec![];
+        match rs.decode_checked(broken.clone()) {
+            Ok(x) => assert_eq!(encoded, x),
+            Err(e) => panic!("{}", e),
+        }
+
+        // 5 present shards can localize the corrupted one.
+        broken[4][2] ^= 1;
+        match rs.decode_checked(broken.clone()) {
+            Err(Error::ParityMismatch(suspects)) => assert_eq!(vec![4], suspects),
+            _ => panic!("The corrupted shard must be detected"),
+        }
+
+        // 4 present shards can only detect it.
+        broken[5] = vec![];
+        match rs.decode_checked(broken.clone()) {
+            Err(Error::ParityMismatch(suspects)) => assert!(suspects.is_empty()),
+            _ => panic!("The corrupted shard must be detected"),
         }
+
+        // 3 present shards can't even detect it.
+        broken[3] = vec![];
+        assert!(rs.decode_checked(broken).is_ok());
     }
+
     #[test]
-    fn test_decode_missing_data_and_parity() {
-        let result = ReedSolomon::new(2, 2);
-        let rs = match result {
+    fn test_encode_iter() {
+        let rs = match ReedSolomon::new(3, 2) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let shards = vec![
-            vec![0, 1, 2],
-            vec![3, 4, 5],
-            vec![200, 201, 203],
-            vec![100, 101, 102],
-        ];
-        let encoded_shard_result = rs.encode(shards.clone());
-        let encoded_shard = match encoded_shard_result {
+        let data: Vec<u8> = (0..12).collect();
+        let mut shards: Vec<Vec<u8>> = data.chunks(4).map(|chunk| chunk.to_vec()).collect();
+        shards.resize(5, vec![0; 4]);
+        let encoded = match rs.encode(shards) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        let broken_shards = vec![vec![0, 1, 2], vec![], vec![], vec![5, 14, 11]];
-        let decoded_shard_result = rs.decode(broken_shards);
-        let decoded_shard = match decoded_shard_result {
+        match rs.encode_iter(data.chunks(4)) {
+            Ok(x) => assert_eq!(encoded[3..], x[..]),
+            Err(e) => panic!("{}", e),
+        }
+
+        assert!(matches!(
+            rs.encode_iter(data.chunks(6)),
+            Err(Error::WrongNoOfShards)
+        ));
+        assert!(matches!(
+            rs.encode_iter(data.chunks(3)),
+            Err(Error::WrongNoOfShards)
+        ));
+        assert!(matches!(
+            rs.encode_iter(data.chunks(5)),
+            Err(Error::InconsistentShards)
+        ));
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<ReedSolomon>();
+        assert_send_sync::<ReedSolomon16>();
+        assert_send_sync::<ReedSolomonConst<4, 2>>();
+        assert_send_sync::<ReedSolomonBuilder>();
+        assert_send_sync::<Matrix>();
+        assert_send_sync::<GaloisField>();
+        assert_send_sync::<verifier::StreamVerifier>();
+        assert_send_sync::<adaptive::AdaptiveEncoder>();
+        assert_send_sync::<scratch::DecodeScratch>();
+        assert_send_sync::<ShardSet>();
+        assert_send_sync::<buffer::ShardBuffer>();
+    }
+
+    #[test]
+    fn test_shared_across_threads() {
+        let rs = match ReedSolomon::new(4, 2) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
-        for (row_index, row) in decoded_shard.iter().enumerate() {
-            for (col_index, &elem) in row.iter().enumerate() {
-                assert_eq!(encoded_shard[row_index][col_index], elem);
+        let rs = Arc::new(rs);
+        let handles: Vec<_> = (0..8u8)
+            .map(|t| {
+                let rs = Arc::clone(&rs);
+                std::thread::spawn(move || {
+                    let mut shards: Vec<Vec<u8>> = (0..4).map(|i| vec![t, i, t ^ i]).collect();
+                    shards.resize(6, vec![0; 3]);
+                    let encoded = match rs.encode(shards) {
+                        Ok(x) => x,
+                        Err(e) => panic!("{}", e),
+                    };
+                    // Every thread decodes its own erasure pattern, so
+                    // the threads share the decode cache.
+                    let mut broken = encoded.clone();
+                    broken[t as usize % 4] = vec![];
+                    broken[4 + t as usize % 2] = vec![];
+                    match rs.decode(broken) {
+                        Ok(x) => assert_eq!(encoded, x),
+                        Err(e) => panic!("{}", e),
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_decode_to_bytes() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let data: Vec<u8> = (0..10).collect();
+            let encoded = match rs.encode(rs.split(&data)) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut broken = encoded.clone();
+            broken[1] = vec![];
+            broken[3] = vec![];
+            match rs.decode_to_bytes(broken, data.len()) {
+                Ok(x) => assert_eq!(data, x),
+                Err(e) => panic!("{}", e),
             }
+            assert!(matches!(
+                rs.decode_to_bytes(encoded, 13),
+                Err(Error::DataLenOutOfBounds(13, 12))
+            ));
         }
     }
 }
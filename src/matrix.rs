@@ -1,8 +1,16 @@
 use crate::error::Error;
 use crate::galois::GaloisField;
 
+/// Budget of field operations of the MDS check of `ReedSolomon::with_matrix`
+/// and `ReedSolomon::with_custom_parity`. It inverts a data shard count
+/// square matrix per combination of rows, so it grows exponentially with
+/// the code, and bigger codes fail with `Error::ConfigTooExpensive`
+/// instead of hanging.
+pub const MAX_MDS_CHECK_OPS: u64 = 1 << 26;
+
 /// A struct to represent Matrix
-pub(crate) struct Matrix {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
     rows: usize,
     cols: usize,
     pub(crate) data: Vec<Vec<u8>>,
@@ -17,11 +25,11 @@ impl Matrix {
     ///
     /// # Example
     /// ```
-    /// use crate::matrix::Matrix;
+    /// use reed_solomon::matrix::Matrix;
     ///
     /// let matrix = Matrix::new(3, 3);
     /// ```
-    pub(crate) fn new(rows: usize, cols: usize) -> Matrix {
+    pub fn new(rows: usize, cols: usize) -> Matrix {
         let data: Vec<Vec<u8>> = vec![vec![0; cols]; rows];
 
         Matrix { rows, cols, data }
@@ -33,7 +41,7 @@ impl Matrix {
     /// * `data` - Matrix data
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::matrix::Matrix;
     ///
     /// let matrix = Matrix::new_from_data(vec![vec![1, 2, 3], vec![1, 2, 3]]);
@@ -46,6 +54,47 @@ impl Matrix {
         }
     }
 
+    /// Create a new matrix by copying the given rows, for callers holding
+    /// slices rather than Vecs.
+    /// # Arguments
+    ///
+    /// * `rows` - Rows of the matrix, all of the same non zero length
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::from_rows(&[&[1, 2, 3], &[1, 2, 3]]).unwrap();
+    /// ```
+    pub fn from_rows(rows: &[&[u8]]) -> Result<Matrix, Error> {
+        let cols = match rows.first() {
+            Some(row) if !row.is_empty() => row.len(),
+            _ => return Err(Error::EmptyMatrix),
+        };
+        if rows.iter().any(|row| row.len() != cols) {
+            return Err(Error::InconsistentRowLengths);
+        }
+
+        Ok(Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.iter().map(|row| row.to_vec()).collect(),
+        })
+    }
+
+    /// Consume the matrix and return its data without cloning it.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new_identity(2);
+    /// assert_eq!(vec![vec![1, 0], vec![0, 1]], matrix.into_data());
+    /// ```
+    pub fn into_data(self) -> Vec<Vec<u8>> {
+        self.data
+    }
+
     /// Create a new identity matrix and fill the primary diagonal
     /// with 1 and the rest with 0s.
     /// # Arguments
@@ -54,15 +103,15 @@ impl Matrix {
     ///
     /// # Example
     /// ```
-    /// use crate::matrix::Matrix;
+    /// use reed_solomon::matrix::Matrix;
     ///
     /// let matrix = Matrix::new_identity(3);
     /// ```
-    pub(crate) fn new_identity(size: usize) -> Matrix {
+    pub fn new_identity(size: usize) -> Matrix {
         let mut data: Vec<Vec<u8>> = vec![vec![0; size]; size];
 
-        for i in 0..size {
-            data[i][i] = 1;
+        for (i, row) in data.iter_mut().enumerate() {
+            row[i] = 1;
         }
 
         Matrix {
@@ -81,20 +130,18 @@ impl Matrix {
     /// * `gf` - Galois Field for the elements of matrix
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::matrix::Matrix;
     /// use crate::galois::GaloisField;
     ///
     /// let gf8 = GaloisField::new();
-    /// let matrix = Matrix::new_vandermonde(3, 3, gf8);
+    /// let matrix = Matrix::new_vandermonde(3, 3, &gf8);
     /// ```
-    pub(crate) fn new_vandermonde(rows: usize, cols: usize, gf: GaloisField) -> Matrix {
+    pub(crate) fn new_vandermonde(rows: usize, cols: usize, gf: &GaloisField) -> Matrix {
         let mut data: Vec<Vec<u8>> = vec![vec![0; cols]; rows];
 
-        for r in 0..rows {
-            for c in 0..cols {
-                data[r][c] = gf.exp(r as u8, c);
-            }
+        for (r, row) in data.iter_mut().enumerate() {
+            gf.fill_vandermonde_row(r as u8, row);
         }
 
         Matrix { rows, cols, data }
@@ -110,7 +157,7 @@ impl Matrix {
     /// * `c_end` - Ending index of the col in given matrix
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::matrix::Matrix;
     ///
     /// let matrix = Matrix::new_identity(3);
@@ -136,13 +183,43 @@ impl Matrix {
         Matrix { rows, cols, data }
     }
 
+    /// Create a new sub matrix from the given matrix (self) made of the
+    /// given rows, in the given order. Unlike `new_sub_matrix` the rows
+    /// don't need to be contiguous.
+    /// # Arguments
+    ///
+    /// * `rows` - Indexes of the rows in given matrix
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new_identity(4);
+    /// let sub_matrix = matrix.submatrix_by_rows(&[0, 2, 3]);
+    /// ```
+    pub fn submatrix_by_rows(&self, rows: &[usize]) -> Result<Matrix, Error> {
+        let mut data: Vec<Vec<u8>> = Vec::with_capacity(rows.len());
+        for &r in rows.iter() {
+            if r >= self.rows {
+                return Err(Error::RowOutOfBounds(r, self.rows));
+            }
+            data.push(self.data[r].clone());
+        }
+
+        Ok(Matrix {
+            rows: rows.len(),
+            cols: self.cols,
+            data,
+        })
+    }
+
     /// Create a new augmented matrix from the given Matrices - self, right.
     /// # Arguments
     ///
     /// * `right` - Right side of the augmented matrix.
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::matrix::Matrix;
     ///
     /// let left = Matrix::new_identity(3);
@@ -156,13 +233,9 @@ impl Matrix {
 
         let cols = self.cols + right.cols;
         let mut data: Vec<Vec<u8>> = vec![vec![0; cols]; self.rows];
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                data[r][c] = self.data[r][c];
-            }
-            for c in 0..right.cols {
-                data[r][self.cols + c] = right.data[r][c];
-            }
+        for (row, (left_row, right_row)) in data.iter_mut().zip(self.data.iter().zip(&right.data)) {
+            row[..self.cols].copy_from_slice(left_row);
+            row[self.cols..].copy_from_slice(right_row);
         }
 
         Ok(Matrix {
@@ -179,14 +252,14 @@ impl Matrix {
     /// * `gf` - Galois Field where the multiplication will occur.
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::matrix::Matrix;
     ///
     /// let left = Matrix::new_identity(3);
     /// let right = Matrix::new_identity(3);
     /// let multiplied_matrix = left.mul(right);
     /// ```
-    pub(crate) fn mul(&self, right: Matrix, gf: GaloisField) -> Result<Matrix, Error> {
+    pub(crate) fn mul(&self, right: Matrix, gf: &GaloisField) -> Result<Matrix, Error> {
         if self.cols != right.rows {
             return Err(Error::RowColMustMatch(self.cols, right.rows));
         }
@@ -212,15 +285,59 @@ impl Matrix {
     /// * `gf` - Galois Field where the multiplication will occur.
     ///
     /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField;
+    /// use crate::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new_from_data(vec![vec![56, 23, 98], vec![3, 100, 200], vec![45, 201, 123]]);
+    /// let gf8 = GaloisField::new();
+    /// let inv_matrix = matrix.invert(&gf8);
     /// ```
+    pub(crate) fn invert(&self, gf: &GaloisField) -> Result<Matrix, Error> {
+        match self.checked_invert(*gf) {
+            Err(Error::SingularAtRow(_)) => Err(Error::SingularMatrix),
+            result => result,
+        }
+    }
+
+    /// Returns the inverted matrix of self. Unlike `invert`, a singular
+    /// matrix gives `Error::SingularAtRow` with the row where the Gaussian
+    /// elimination couldn't find a non-zero pivot, while a non-square
+    /// matrix gives `Error::NonSquareMatrix`.
+    /// # Arguments
+    ///
+    /// * `gf` - Galois Field where the multiplication will occur.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::galois::GaloisField;
+    /// use reed_solomon::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::from_rows(&[&[1, 2], &[2, 4]]).unwrap();
+    /// let gf8 = GaloisField::new();
+    /// assert!(matrix.checked_invert(gf8).is_err());
+    /// ```
+    pub fn checked_invert(&self, gf: GaloisField) -> Result<Matrix, Error> {
+        self.invert_with(&gf, cfg!(feature = "rayon"))
+    }
+
+    /// Returns the inverted matrix of self, choosing whether the row
+    /// elimination of the Gaussian elimination runs in parallel.
+    /// # Arguments
+    ///
+    /// * `gf` - Galois Field where the multiplication will occur.
+    /// * `parallel` - Whether to eliminate the rows below each pivot in parallel.
+    ///
+    /// # Example
+    /// ```ignore
     /// use crate::galois::GaloisField;
     /// use crate::matrix::Matrix;
     ///
     /// let matrix = Matrix::new_from_data(vec![vec![56, 23, 98], vec![3, 100, 200], vec![45, 201, 123]]);
     /// let gf8 = GaloisField::new();
-    /// let inv_matrix = matrix.invert(gf8);
+    /// let inv_matrix = matrix.invert_with(&gf8, false);
     /// ```
-    pub(crate) fn invert(&self, gf: GaloisField) -> Result<Matrix, Error> {
+    fn invert_with(&self, gf: &GaloisField, parallel: bool) -> Result<Matrix, Error> {
         if self.rows != self.cols {
             return Err(Error::NonSquareMatrix);
         }
@@ -228,12 +345,87 @@ impl Matrix {
         let mut work = self.new_augmented_matrix(Matrix::new_identity(self.rows))?;
 
         // Use Gaussian elimination to transform the left half of working matrix into an identity matrix.
-        work.gauss_elim(gf)?;
+        work.gauss_elim(gf, parallel)?;
 
         // The right half is now the inverse matrix.
         Ok(work.new_sub_matrix(0, self.rows, self.cols, self.cols * 2))
     }
 
+    /// Check whether every square matrix made of cols rows of the matrix
+    /// (self) is invertible, which is the MDS property of an encoding
+    /// matrix: any data shard count shards are enough to decode. Every
+    /// combination of rows is tried, so it fails with
+    /// `Error::ConfigTooExpensive` when that takes more than
+    /// `MAX_MDS_CHECK_OPS` operations.
+    /// # Arguments
+    ///
+    /// * `gf` - Galois Field where the multiplication will occur.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField;
+    /// use crate::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new_from_data(vec![vec![1, 0], vec![0, 1], vec![1, 1], vec![1, 2]]);
+    /// let gf8 = GaloisField::new();
+    /// assert!(matrix.is_mds(&gf8).unwrap());
+    /// ```
+    pub(crate) fn is_mds(&self, gf: &GaloisField) -> Result<bool, Error> {
+        let size = self.cols;
+        if size > self.rows {
+            return Ok(false);
+        }
+        let ops = self.mds_check_ops();
+        if ops > MAX_MDS_CHECK_OPS {
+            return Err(Error::ConfigTooExpensive(ops, MAX_MDS_CHECK_OPS));
+        }
+
+        // Walk through every combination of size rows in lexicographic order.
+        let mut rows: Vec<usize> = (0..size).collect();
+        loop {
+            match self.submatrix_by_rows(&rows) {
+                Ok(sub_matrix) => {
+                    if sub_matrix.invert(gf).is_err() {
+                        return Ok(false);
+                    }
+                }
+                Err(_) => return Ok(false),
+            }
+
+            // Find the rightmost row which can still be moved down.
+            let mut i = size;
+            while i > 0 && rows[i - 1] == self.rows - size + i - 1 {
+                i -= 1;
+            }
+            if i == 0 {
+                return Ok(true);
+            }
+            rows[i - 1] += 1;
+            for j in i..size {
+                rows[j] = rows[j - 1] + 1;
+            }
+        }
+    }
+
+    /// Returns the estimated no. of field operations of `is_mds`, i.e. the
+    /// no. of combinations of cols rows times the cube of cols, saturating
+    /// at u64::MAX.
+    fn mds_check_ops(&self) -> u64 {
+        let size = self.cols as u128;
+        let rows = self.rows as u128;
+        let mut combinations: u128 = 1;
+        for i in 0..size.min(rows - size) {
+            // Stays exact as every prefix of the product is a binomial
+            // coefficient, and can't overflow as it stops above u64::MAX.
+            combinations = combinations * (rows - i) / (i + 1);
+            if combinations > u64::MAX as u128 {
+                return u64::MAX;
+            }
+        }
+
+        u64::try_from(combinations * size.pow(3)).unwrap_or(u64::MAX)
+    }
+
     /// Swap two given rows of Matrix data.
     /// # Arguments
     ///
@@ -241,7 +433,7 @@ impl Matrix {
     /// * `row2` - 2nd row to be swapped in the given matrix.
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::matrix::Matrix;
     ///
     /// let matrix = Matrix::new_identity(3);
@@ -255,21 +447,50 @@ impl Matrix {
         self.data.swap(row1, row2);
     }
 
+    /// Swap two given rows of Matrix data after checking that both
+    /// rows are inside the matrix.
+    /// # Arguments
+    ///
+    /// * `row1` - 1st row to be swapped in the given matrix.
+    /// * `row2` - 2nd row to be swapped in the given matrix.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::matrix::Matrix;
+    ///
+    /// let mut matrix = Matrix::new_identity(3);
+    /// matrix.try_swap_rows(0, 1).unwrap();
+    /// assert!(matrix.try_swap_rows(0, 3).is_err());
+    /// ```
+    pub fn try_swap_rows(&mut self, row1: usize, row2: usize) -> Result<(), Error> {
+        if row1 >= self.rows {
+            return Err(Error::RowOutOfBounds(row1, self.rows));
+        }
+        if row2 >= self.rows {
+            return Err(Error::RowOutOfBounds(row2, self.rows));
+        }
+
+        self.swap_rows(row1, row2);
+
+        Ok(())
+    }
+
     /// Perform Gaussian Elimination on the given matrix (self)
     /// # Arguments
     ///
     /// * `gf` - Galois Field where the multiplication will occur.
+    /// * `parallel` - Whether to eliminate the rows below each pivot in parallel.
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// use crate::galois::GaloisField;
     /// use crate::matrix::Matrix;
     ///
     /// let matrix = Matrix::new_from_data(vec![vec![56, 23, 98], vec![3, 100, 200], vec![45, 201, 123]]);
     /// let gf8 = GaloisField::new();
-    /// matrix.gauss_elim(gf8);
+    /// matrix.gauss_elim(&gf8, false);
     /// ```
-    fn gauss_elim(&mut self, gf: GaloisField) -> Result<(), Error> {
+    fn gauss_elim(&mut self, gf: &GaloisField, parallel: bool) -> Result<(), Error> {
         // Clear out the lower triangle below the main diagonal and scale the main diagonal to be 1.
         for r in 0..self.rows {
             // If the element on the diagonal is 0, find a row below
@@ -284,7 +505,7 @@ impl Matrix {
             }
             // If we couldn't find one, the matrix is singular.
             if self.data[r][r] == 0 {
-                return Err(Error::SingularMatrix);
+                return Err(Error::SingularAtRow(r));
             }
             // Scale to 1.
             if self.data[r][r] != 1 {
@@ -296,14 +517,10 @@ impl Matrix {
             // Make everything below the 1 be a 0 by subtracting
             // a multiple of it. (Subtraction and addition are
             // both exclusive or in the Galois field.)
-            for r_below in r + 1..self.rows {
-                if self.data[r_below][r] != 0 {
-                    let scale = self.data[r_below][r];
-                    for c in 0..self.cols {
-                        let m = gf.mul(scale, self.data[r][c]);
-                        self.data[r_below][c] = GaloisField::add(self.data[r_below][c], m);
-                    }
-                }
+            if parallel {
+                self.eliminate_below_par(r, gf);
+            } else {
+                self.eliminate_below(r, gf);
             }
         }
         // Now clear the upper triangle above the main diagonal.
@@ -321,6 +538,80 @@ impl Matrix {
 
         Ok(())
     }
+
+    /// Subtract a multiple of the pivot row from every row below it so
+    /// that the pivot column below the diagonal becomes 0.
+    /// # Arguments
+    ///
+    /// * `r` - Index of the pivot row. Its diagonal element must be 1.
+    /// * `gf` - Galois Field where the multiplication will occur.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField;
+    /// use crate::matrix::Matrix;
+    ///
+    /// let mut matrix = Matrix::new_from_data(vec![vec![1, 23, 98], vec![3, 100, 200], vec![45, 201, 123]]);
+    /// let gf8 = GaloisField::new();
+    /// matrix.eliminate_below(0, &gf8);
+    /// ```
+    fn eliminate_below(&mut self, r: usize, gf: &GaloisField) {
+        let (top, below) = self.data.split_at_mut(r + 1);
+        let pivot = &top[r];
+        for row in below.iter_mut() {
+            Self::eliminate_row(row, pivot, r, gf);
+        }
+    }
+
+    /// Same as `eliminate_below` but the rows below the pivot are
+    /// processed in parallel. Every row only depends on itself and the
+    /// pivot row, so the result is identical to the serial version.
+    /// # Arguments
+    ///
+    /// * `r` - Index of the pivot row. Its diagonal element must be 1.
+    /// * `gf` - Galois Field where the multiplication will occur.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use crate::galois::GaloisField;
+    /// use crate::matrix::Matrix;
+    ///
+    /// let mut matrix = Matrix::new_from_data(vec![vec![1, 23, 98], vec![3, 100, 200], vec![45, 201, 123]]);
+    /// let gf8 = GaloisField::new();
+    /// matrix.eliminate_below_par(0, &gf8);
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn eliminate_below_par(&mut self, r: usize, gf: &GaloisField) {
+        use rayon::prelude::*;
+
+        let (top, below) = self.data.split_at_mut(r + 1);
+        let pivot = &top[r];
+        below
+            .par_iter_mut()
+            .for_each(|row| Self::eliminate_row(row, pivot, r, gf));
+    }
+
+    /// Without the `rayon` feature there is nothing to run in parallel,
+    /// so this falls back to the serial elimination.
+    #[cfg(not(feature = "rayon"))]
+    fn eliminate_below_par(&mut self, r: usize, gf: &GaloisField) {
+        self.eliminate_below(r, gf);
+    }
+
+    /// Subtract the multiple of the pivot row which makes `row[col]` 0.
+    /// # Arguments
+    ///
+    /// * `row` - Row to be reduced.
+    /// * `pivot` - Pivot row whose element at `col` is 1.
+    /// * `col` - Index of the pivot column.
+    /// * `gf` - Galois Field where the multiplication will occur.
+    fn eliminate_row(row: &mut [u8], pivot: &[u8], col: usize, gf: &GaloisField) {
+        let scale = row[col];
+        if scale == 0 {
+            return;
+        }
+        gf.mul_add_slice(scale, pivot, row);
+    }
 }
 
 #[cfg(test)]
@@ -356,6 +647,42 @@ mod tests {
         }
     }
     #[test]
+    fn test_from_rows() {
+        let row: [u8; 3] = [1, 2, 3];
+        let rows: [&[u8]; 2] = [&row, &[4, 5, 6]];
+        let matrix = match Matrix::from_rows(&rows) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        assert_eq!(matrix.rows, 2);
+        assert_eq!(matrix.cols, 3);
+        assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], matrix.data);
+
+        assert!(matches!(Matrix::from_rows(&[]), Err(Error::EmptyMatrix)));
+        assert!(matches!(Matrix::from_rows(&[&[]]), Err(Error::EmptyMatrix)));
+        assert!(matches!(
+            Matrix::from_rows(&[&[1, 2], &[3]]),
+            Err(Error::InconsistentRowLengths)
+        ));
+    }
+    #[test]
+    fn test_into_data() {
+        let gf8 = GaloisField::new();
+        let matrix = Matrix::new_vandermonde(3, 3, &gf8);
+        let before = matrix.data.clone();
+        let data = matrix.into_data();
+        let exp_res: [[u8; 3]; 3] = [[1, 0, 0], [1, 1, 1], [1, 2, 4]];
+
+        assert_eq!(before, data);
+        assert_eq!(data.len(), 3);
+        for (row_index, row) in data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
     fn test_new_identity() {
         let matrix = Matrix::new_identity(3);
 
@@ -376,7 +703,7 @@ mod tests {
     #[test]
     fn test_new_vandermonde() {
         let gf8 = GaloisField::new();
-        let matrix = Matrix::new_vandermonde(3, 3, gf8);
+        let matrix = Matrix::new_vandermonde(3, 3, &gf8);
         let exp_res: [[u8; 3]; 3] = [[1, 0, 0], [1, 1, 1], [1, 2, 4]];
 
         assert_eq!(matrix.rows, 3);
@@ -392,7 +719,7 @@ mod tests {
     #[test]
     fn test_new_sub_matrix() {
         let gf8 = GaloisField::new();
-        let matrix = Matrix::new_vandermonde(3, 3, gf8);
+        let matrix = Matrix::new_vandermonde(3, 3, &gf8);
         let sub_matrix = matrix.new_sub_matrix(1, matrix.rows, 1, matrix.cols);
         let exp_res: [[u8; 2]; 2] = [[1, 1], [2, 4]];
 
@@ -407,9 +734,35 @@ mod tests {
         }
     }
     #[test]
+    fn test_submatrix_by_rows() {
+        let gf8 = GaloisField::new();
+        let matrix = Matrix::new_vandermonde(4, 3, &gf8);
+        let result = matrix.submatrix_by_rows(&[0, 2, 3]);
+        let sub_matrix = match result {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let exp_res: [[u8; 3]; 3] = [[1, 0, 0], [1, 2, 4], [1, 3, 5]];
+
+        assert_eq!(sub_matrix.rows, 3);
+        assert_eq!(sub_matrix.cols, 3);
+        assert_eq!(sub_matrix.data.len(), 3);
+        assert_eq!(sub_matrix.data[0].len(), 3);
+        for (row_index, row) in sub_matrix.data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+
+        assert!(matches!(
+            matrix.submatrix_by_rows(&[0, 4]),
+            Err(Error::RowOutOfBounds(4, 4))
+        ));
+    }
+    #[test]
     fn test_new_augmented_matrix() {
         let gf8 = GaloisField::new();
-        let left = Matrix::new_vandermonde(3, 3, gf8);
+        let left = Matrix::new_vandermonde(3, 3, &gf8);
         let right = Matrix::new_identity(3);
         let result = left.new_augmented_matrix(right);
         let res = match result {
@@ -433,7 +786,7 @@ mod tests {
         let gf8 = GaloisField::new();
         let left = Matrix::new_from_data(vec![vec![1, 2], vec![3, 4]]);
         let right = Matrix::new_from_data(vec![vec![5, 6], vec![7, 8]]);
-        let result = left.mul(right, gf8);
+        let result = left.mul(right, &gf8);
         let res = match result {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
@@ -453,7 +806,7 @@ mod tests {
     #[test]
     fn test_new_swap() {
         let gf8 = GaloisField::new();
-        let mut matrix = Matrix::new_vandermonde(3, 3, gf8);
+        let mut matrix = Matrix::new_vandermonde(3, 3, &gf8);
         matrix.swap_rows(0, 1);
         let exp_res: [[u8; 3]; 3] = [[1, 1, 1], [1, 0, 0], [1, 2, 4]];
 
@@ -475,7 +828,7 @@ mod tests {
             vec![3, 100, 200],
             vec![45, 201, 123],
         ]);
-        let result = matrix.invert(gf8);
+        let result = matrix.invert(&gf8);
         let res = match result {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
@@ -489,7 +842,7 @@ mod tests {
             }
         }
 
-        let multiplied = matrix.mul(res, gf8);
+        let multiplied = matrix.mul(res, &gf8);
         let mul = match multiplied {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
@@ -501,4 +854,108 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn test_invert_parallel_matches_serial() {
+        let gf8 = GaloisField::new();
+        let matrix = Matrix::new_vandermonde(128, 128, &gf8);
+        let serial = match matrix.invert_with(&gf8, false) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let parallel = match matrix.invert_with(&gf8, true) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        assert_eq!(serial.data, parallel.data);
+    }
+    #[test]
+    fn test_try_swap_rows() {
+        let gf8 = GaloisField::new();
+        let mut matrix = Matrix::new_vandermonde(3, 3, &gf8);
+        if let Err(e) = matrix.try_swap_rows(0, 1) {
+            panic!("{}", e);
+        }
+        let exp_res: [[u8; 3]; 3] = [[1, 1, 1], [1, 0, 0], [1, 2, 4]];
+
+        for (row_index, row) in matrix.data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+
+        assert!(matches!(
+            matrix.try_swap_rows(3, 0),
+            Err(Error::RowOutOfBounds(3, 3))
+        ));
+        assert!(matches!(
+            matrix.try_swap_rows(0, 5),
+            Err(Error::RowOutOfBounds(5, 3))
+        ));
+        for (row_index, row) in matrix.data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+    }
+    #[test]
+    fn test_is_mds() {
+        let gf8 = GaloisField::new();
+        let mds = Matrix::new_from_data(vec![vec![1, 0], vec![0, 1], vec![1, 1], vec![1, 2]]);
+        assert!(matches!(mds.is_mds(&gf8), Ok(true)));
+
+        let not_mds = Matrix::new_from_data(vec![vec![1, 0], vec![0, 1], vec![0, 1], vec![1, 2]]);
+        assert!(matches!(not_mds.is_mds(&gf8), Ok(false)));
+
+        let vandermonde = Matrix::new_vandermonde(6, 3, &gf8);
+        assert!(matches!(vandermonde.is_mds(&gf8), Ok(true)));
+
+        let vandermonde = Matrix::new_vandermonde(64, 32, &gf8);
+        assert!(matches!(
+            vandermonde.is_mds(&gf8),
+            Err(Error::ConfigTooExpensive(_, MAX_MDS_CHECK_OPS))
+        ));
+    }
+    #[test]
+    fn test_checked_invert() {
+        let gf8 = GaloisField::new();
+        let matrix = Matrix::new_from_data(vec![
+            vec![56, 23, 98],
+            vec![3, 100, 200],
+            vec![45, 201, 123],
+        ]);
+        let res = match matrix.checked_invert(gf8) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let exp_res: [[u8; 3]; 3] = [[175, 133, 33], [130, 13, 245], [112, 35, 126]];
+        for (row_index, row) in res.data.iter().enumerate() {
+            for (col_index, &elem) in row.iter().enumerate() {
+                assert_eq!(exp_res[row_index][col_index], elem);
+            }
+        }
+
+        let non_square = Matrix::new(2, 3);
+        assert!(matches!(
+            non_square.checked_invert(gf8),
+            Err(Error::NonSquareMatrix)
+        ));
+
+        let zero_column = Matrix::new_from_data(vec![vec![0, 1], vec![0, 1]]);
+        assert!(matches!(
+            zero_column.checked_invert(gf8),
+            Err(Error::SingularAtRow(0))
+        ));
+
+        // The 2nd row is twice the 1st one.
+        let dependent_rows = Matrix::new_from_data(vec![vec![1, 2], vec![2, 4]]);
+        assert!(matches!(
+            dependent_rows.checked_invert(gf8),
+            Err(Error::SingularAtRow(1))
+        ));
+        assert!(matches!(
+            dependent_rows.invert(&gf8),
+            Err(Error::SingularMatrix)
+        ));
+    }
 }
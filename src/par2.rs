@@ -0,0 +1,270 @@
+use crate::error::Error;
+use crate::galois::GaloisField16;
+
+/// Max no. of data shards of PAR2, i.e. the no. of logs which are
+/// coprime to 2^16 - 1 = 3 * 5 * 17 * 257.
+pub const MAX_DATA_SHARDS: usize = 32768;
+
+/// A Struct to compute the recovery slices of PAR2 2.0, so that they can
+/// be verified and repaired by PAR2 tooling and vice versa. PAR2 works on
+/// 16 bit little endian words in GF(2^16) with the polynomial 0x1100B.
+/// The data shard i has the constant c_i = 2^n_i, where n_i is the i-th
+/// log which is not divisible by 3, 5, 17 or 257, and the recovery slice
+/// with exponent e is the sum of c_i^e times data shard i. Unlike the
+/// matrix of `ReedSolomon`, the top square isn't inverted, so some
+/// combinations of lost data shards and recovery slices can't decode.
+/// Only the slices are computed, reading and writing the packets of the
+/// PAR2 files is left to the caller.
+pub struct Par2Code {
+    gf: GaloisField16,
+    constants: Vec<u16>,
+    exponents: Vec<u16>,
+}
+
+impl Par2Code {
+    /// Create a new Par2Code with the given recovery slice exponents.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards i.e. input slices
+    /// * `exponents` - Exponent of every recovery slice
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::par2::Par2Code;
+    ///
+    /// let par2 = Par2Code::new(4, vec![0, 1]).unwrap();
+    /// ```
+    pub fn new(data_shards: usize, exponents: Vec<u16>) -> Result<Par2Code, Error> {
+        if data_shards == 0 {
+            return Err(Error::ZeroDataShards);
+        }
+        if exponents.is_empty() {
+            return Err(Error::ZeroParityShards);
+        }
+        if data_shards > MAX_DATA_SHARDS {
+            return Err(Error::TooManyShardsForField(data_shards, MAX_DATA_SHARDS));
+        }
+
+        let gf = GaloisField16::new();
+        let constants: Vec<u16> = (1..)
+            .filter(|n| n % 3 != 0 && n % 5 != 0 && n % 17 != 0 && n % 257 != 0)
+            .take(data_shards)
+            .map(|n| gf.exp2(n))
+            .collect();
+
+        Ok(Par2Code {
+            gf,
+            constants,
+            exponents,
+        })
+    }
+
+    /// Returns the constant of every data shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::par2::Par2Code;
+    ///
+    /// let par2 = Par2Code::new(4, vec![0, 1]).unwrap();
+    /// assert_eq!(&[2, 4, 16, 128], par2.input_constants());
+    /// ```
+    pub fn input_constants(&self) -> &[u16] {
+        &self.constants
+    }
+
+    /// Computes the recovery slices of the given data shards.
+    /// Returns one recovery slice per exponent.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards, all of the same even length
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::par2::Par2Code;
+    ///
+    /// let par2 = Par2Code::new(2, vec![0]).unwrap();
+    /// let data: [&[u8]; 2] = [&[1, 2], &[3, 4]];
+    /// let recovery = par2.encode(&data).unwrap();
+    /// assert_eq!(vec![vec![2, 6]], recovery);
+    /// ```
+    pub fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        if data.len() != self.constants.len() {
+            return Err(Error::WrongNoOfShards);
+        }
+        let lens: Vec<Option<usize>> = data.iter().map(|shard| Some(shard.len())).collect();
+        let shard_elem_len = Self::check_lens(&lens)?;
+
+        let mut recovery: Vec<Vec<u8>> = vec![vec![0; shard_elem_len]; self.exponents.len()];
+        for (&exponent, output) in self.exponents.iter().zip(recovery.iter_mut()) {
+            for (&constant, &shard) in self.constants.iter().zip(data.iter()) {
+                let coefficient = self.gf.exp(constant, exponent as usize);
+                self.gf.mul_add_words(coefficient, shard, output);
+            }
+        }
+
+        Ok(recovery)
+    }
+
+    /// Recover the missing data shards in place from the present data
+    /// shards and recovery slices. Returns `Error::SingularMatrix` if the
+    /// lost data shards can't be recovered from the present recovery
+    /// slices, which can happen with PAR2.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards. Missing shards are `None`.
+    /// * `recovery` - Recovery slices in the order of the exponents. Missing slices are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::par2::Par2Code;
+    ///
+    /// let par2 = Par2Code::new(2, vec![0]).unwrap();
+    /// let mut data = vec![Some(vec![1, 2]), None];
+    /// par2.reconstruct(&mut data, &[Some(vec![2, 6])]).unwrap();
+    /// assert_eq!(Some(vec![3, 4]), data[1]);
+    /// ```
+    pub fn reconstruct(
+        &self,
+        data: &mut [Option<Vec<u8>>],
+        recovery: &[Option<Vec<u8>>],
+    ) -> Result<(), Error> {
+        if data.len() != self.constants.len() || recovery.len() != self.exponents.len() {
+            return Err(Error::WrongNoOfShards);
+        }
+        let lens: Vec<Option<usize>> = data
+            .iter()
+            .chain(recovery.iter())
+            .map(|shard| shard.as_ref().map(|x| x.len()))
+            .collect();
+        let shard_elem_len = Self::check_lens(&lens)?;
+
+        let missing: Vec<usize> = (0..data.len()).filter(|&i| data[i].is_none()).collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let used: Vec<usize> = (0..recovery.len())
+            .filter(|&r| recovery[r].is_some())
+            .take(missing.len())
+            .collect();
+        if used.len() < missing.len() {
+            return Err(Error::TooFewShards);
+        }
+
+        // Subtract the present data shards from the recovery slices, which
+        // leaves the sum of the missing data shards times their
+        // coefficients in every one of them.
+        let mut syndromes: Vec<Vec<u8>> = Vec::with_capacity(used.len());
+        for &r in used.iter() {
+            let mut syndrome = recovery[r].clone().unwrap_or_default();
+            let exponent = self.exponents[r] as usize;
+            for (&constant, shard) in self.constants.iter().zip(data.iter()) {
+                if let Some(shard) = shard {
+                    let coefficient = self.gf.exp(constant, exponent);
+                    self.gf.mul_add_words(coefficient, shard, &mut syndrome);
+                }
+            }
+            syndromes.push(syndrome);
+        }
+
+        let coefficients: Vec<Vec<u16>> = used
+            .iter()
+            .map(|&r| {
+                missing
+                    .iter()
+                    .map(|&i| self.gf.exp(self.constants[i], self.exponents[r] as usize))
+                    .collect()
+            })
+            .collect();
+        let inverse = match self.gf.invert_matrix(coefficients) {
+            Some(x) => x,
+            None => return Err(Error::SingularMatrix),
+        };
+
+        for (&i, row) in missing.iter().zip(inverse.iter()) {
+            let mut shard: Vec<u8> = vec![0; shard_elem_len];
+            for (&coefficient, syndrome) in row.iter().zip(syndromes.iter()) {
+                self.gf.mul_add_words(coefficient, syndrome, &mut shard);
+            }
+            data[i] = Some(shard);
+        }
+
+        Ok(())
+    }
+
+    /// Check that the present shards are non empty and have the same even
+    /// length. Returns the length of the shards.
+    fn check_lens(lens: &[Option<usize>]) -> Result<usize, Error> {
+        let shard_elem_len = match lens.iter().flatten().next() {
+            Some(&x) => x,
+            None => return Err(Error::TooFewShards),
+        };
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if lens.iter().flatten().any(|&len| len != shard_elem_len) {
+            return Err(Error::InconsistentShards);
+        }
+        if shard_elem_len % 2 != 0 {
+            return Err(Error::OddShardLength(shard_elem_len));
+        }
+
+        Ok(shard_elem_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_constants() {
+        let par2 = match Par2Code::new(8, vec![0]) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        // 2^1, 2^2, 2^4, 2^7, 2^8, 2^11, 2^13 and 2^14 as 3, 5, 6, 9,
+        // 10 and 12 are skipped.
+        assert_eq!(
+            &[2, 4, 16, 128, 256, 2048, 8192, 16384],
+            par2.input_constants()
+        );
+        assert!(Par2Code::new(MAX_DATA_SHARDS + 1, vec![0]).is_err());
+    }
+    #[test]
+    fn test_encode_reconstruct() {
+        let par2 = match Par2Code::new(4, vec![0, 1, 5]) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<Vec<u8>> = (0..4)
+            .map(|i| (0..6).map(|j| (i * 37 + j * 11) as u8).collect())
+            .collect();
+        let refs: Vec<&[u8]> = data.iter().map(|shard| &shard[..]).collect();
+        let recovery = match par2.encode(&refs) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        // The recovery slice with exponent 0 is the XOR of the data.
+        let xor: Vec<u8> = (0..6)
+            .map(|j| data.iter().fold(0, |acc, shard| acc ^ shard[j]))
+            .collect();
+        assert_eq!(xor, recovery[0]);
+
+        let mut broken: Vec<Option<Vec<u8>>> = data.iter().cloned().map(Some).collect();
+        broken[0] = None;
+        broken[3] = None;
+        let recovery: Vec<Option<Vec<u8>>> =
+            vec![None, Some(recovery[1].clone()), Some(recovery[2].clone())];
+        if let Err(e) = par2.reconstruct(&mut broken, &recovery) {
+            panic!("{}", e);
+        }
+        let decoded: Vec<Vec<u8>> = broken.into_iter().flatten().collect();
+        assert_eq!(data, decoded);
+
+        assert!(matches!(
+            par2.encode(&[&[0, 1, 2], &[0, 1, 2], &[0, 1, 2], &[0, 1, 2]]),
+            Err(Error::OddShardLength(3))
+        ));
+    }
+}
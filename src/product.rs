@@ -0,0 +1,236 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+
+/// A Struct to encode a 2D grid of bytes with one code along the rows
+/// and another code along the columns. A burst of lost cells which is
+/// too big for one row or one column can still be recovered by going
+/// back and forth between rows and columns.
+pub struct ProductCode {
+    row_code: ReedSolomon,
+    col_code: ReedSolomon,
+}
+
+impl ProductCode {
+    /// Create a new ProductCode from the given codes.
+    /// # Arguments
+    ///
+    /// * `row_code` - Code applied to every row, its data shard count is the width of the grid
+    /// * `col_code` - Code applied to every column, its data shard count is the height of the grid
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::product::ProductCode;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let row_code = ReedSolomon::new(4, 2).unwrap();
+    /// let col_code = ReedSolomon::new(3, 2).unwrap();
+    /// let product = ProductCode::new(row_code, col_code);
+    /// ```
+    pub fn new(row_code: ReedSolomon, col_code: ReedSolomon) -> ProductCode {
+        ProductCode { row_code, col_code }
+    }
+
+    /// Encodes the grid by applying the row code to every row and then
+    /// the column code to every column, including the parity columns.
+    /// Returns the encoded grid, which has the total shard count of the
+    /// column code as rows and the total shard count of the row code as
+    /// columns.
+    /// # Arguments
+    ///
+    /// * `grid` - Rows of data bytes, as many as the data shard count of the column code,
+    ///   each as long as the data shard count of the row code.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::product::ProductCode;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let row_code = ReedSolomon::new(2, 1).unwrap();
+    /// let col_code = ReedSolomon::new(2, 1).unwrap();
+    /// let product = ProductCode::new(row_code, col_code);
+    /// let encoded = product.encode_2d(vec![vec![0, 1], vec![2, 3]]).unwrap();
+    /// assert_eq!(3, encoded.len());
+    /// ```
+    pub fn encode_2d(&self, grid: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        if grid.len() != self.col_code.data_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        if grid
+            .iter()
+            .any(|row| row.len() != self.row_code.data_shard_count)
+        {
+            return Err(Error::InconsistentShards);
+        }
+
+        let mut encoded: Vec<Vec<u8>> = Vec::with_capacity(self.col_code.total_shard_count);
+        for row in grid.iter() {
+            encoded.push(Self::encode_line(&self.row_code, row)?);
+        }
+
+        let mut cols: Vec<Vec<u8>> = Vec::with_capacity(self.row_code.total_shard_count);
+        for c in 0..self.row_code.total_shard_count {
+            let col: Vec<u8> = encoded.iter().map(|row| row[c]).collect();
+            cols.push(Self::encode_line(&self.col_code, &col)?);
+        }
+
+        encoded = (0..self.col_code.total_shard_count)
+            .map(|r| cols.iter().map(|col| col[r]).collect())
+            .collect();
+
+        Ok(encoded)
+    }
+
+    /// Recover the missing cells of an encoded grid. Every row and every
+    /// column with enough cells present is decoded, which may fill in
+    /// enough cells for other rows and columns, until the grid is
+    /// complete or no more progress can be made.
+    /// Returns the complete encoded grid.
+    /// # Arguments
+    ///
+    /// * `grid` - Encoded grid returned by `encode_2d`, with the lost cells set to None.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::product::ProductCode;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let row_code = ReedSolomon::new(2, 1).unwrap();
+    /// let col_code = ReedSolomon::new(2, 1).unwrap();
+    /// let product = ProductCode::new(row_code, col_code);
+    /// let encoded = product.encode_2d(vec![vec![0, 1], vec![2, 3]]).unwrap();
+    /// let mut broken: Vec<Vec<Option<u8>>> = encoded
+    ///     .iter()
+    ///     .map(|row| row.iter().map(|&x| Some(x)).collect())
+    ///     .collect();
+    /// broken[0][0] = None;
+    /// broken[0][1] = None;
+    /// assert_eq!(encoded, product.decode_2d(broken).unwrap());
+    /// ```
+    pub fn decode_2d(&self, grid: Vec<Vec<Option<u8>>>) -> Result<Vec<Vec<u8>>, Error> {
+        let rows = self.col_code.total_shard_count;
+        let cols = self.row_code.total_shard_count;
+        if grid.len() != rows {
+            return Err(Error::WrongNoOfShards);
+        }
+        if grid.iter().any(|row| row.len() != cols) {
+            return Err(Error::InconsistentShards);
+        }
+
+        let mut grid = grid;
+        loop {
+            let mut progress = false;
+
+            for line in grid.iter_mut() {
+                if let Some(row) = Self::decode_line(&self.row_code, line)? {
+                    for (elem, x) in line.iter_mut().zip(row) {
+                        *elem = Some(x);
+                    }
+                    progress = true;
+                }
+            }
+
+            for c in 0..cols {
+                let col: Vec<Option<u8>> = grid.iter().map(|row| row[c]).collect();
+                if let Some(col) = Self::decode_line(&self.col_code, &col)? {
+                    for r in 0..rows {
+                        grid[r][c] = Some(col[r]);
+                    }
+                    progress = true;
+                }
+            }
+
+            if !progress {
+                break;
+            }
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<Option<Vec<u8>>>())
+            .collect::<Option<Vec<Vec<u8>>>>()
+            .ok_or(Error::TooFewShards)
+    }
+
+    /// Encodes a line of data bytes, every byte being a shard of its own.
+    fn encode_line(rs: &ReedSolomon, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut shards: Vec<Vec<u8>> = data.iter().map(|&x| vec![x]).collect();
+        shards.resize(rs.total_shard_count, vec![0]);
+
+        Ok(rs
+            .encode(shards)?
+            .into_iter()
+            .map(|shard| shard[0])
+            .collect())
+    }
+
+    /// Decodes a line of cells if some of them are missing and enough of
+    /// them are present. Returns None if there is nothing to be done.
+    fn decode_line(rs: &ReedSolomon, line: &[Option<u8>]) -> Result<Option<Vec<u8>>, Error> {
+        let present = line.iter().filter(|x| x.is_some()).count();
+        if present == line.len() || present < rs.data_shard_count {
+            return Ok(None);
+        }
+
+        let shards: Vec<Option<Vec<u8>>> = line.iter().map(|x| x.map(|x| vec![x])).collect();
+        let decoded = rs.decode_options(shards)?;
+
+        Ok(Some(decoded.into_iter().map(|shard| shard[0]).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_2d() {
+        let row_code = match ReedSolomon::new(4, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let col_code = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let product = ProductCode::new(row_code, col_code);
+        let grid: Vec<Vec<u8>> = (0..3)
+            .map(|r| (0..4).map(|c| (r * 4 + c) as u8 * 17).collect())
+            .collect();
+
+        let encoded = match product.encode_2d(grid.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(5, encoded.len());
+        assert_eq!(6, encoded[0].len());
+        for r in 0..3 {
+            assert_eq!(grid[r][..], encoded[r][..4]);
+        }
+
+        // The burst is too wide for the code of row 0 and too tall for
+        // the code of column 0, but rows 1 and 2 can be decoded first.
+        let mut broken: Vec<Vec<Option<u8>>> = encoded
+            .iter()
+            .map(|row| row.iter().map(|&x| Some(x)).collect())
+            .collect();
+        broken[0][..3].fill(None);
+        for row in broken[..3].iter_mut() {
+            row[0] = None;
+        }
+        let decoded = match product.decode_2d(broken.clone()) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(encoded, decoded);
+
+        // Losing every cell of 3 rows can't be recovered.
+        for row in broken[..3].iter_mut() {
+            row[..6].fill(None);
+        }
+        assert!(matches!(
+            product.decode_2d(broken),
+            Err(Error::TooFewShards)
+        ));
+
+        assert!(product.encode_2d(grid[..2].to_vec()).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+
+/// Length of every shard of the stripes of the preset profiles. Large
+/// enough to amortize the per-stripe work, small enough to repair a
+/// stripe in memory.
+const PROFILE_SHARD_LEN: usize = 256 * 1024;
+
+/// A ready-made layout of a code, i.e. its no. of data and parity shards
+/// and the no. of bytes of data held by every stripe.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// No. of data shards.
+    pub data_shards: usize,
+    /// No. of parity shards.
+    pub parity_shards: usize,
+    /// Recommended no. of bytes of data held by every stripe, e.g. for
+    /// `Striper::new`.
+    pub stripe_size: usize,
+}
+
+impl Profile {
+    /// Create the code of the profile.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::profiles;
+    ///
+    /// let rs = profiles::rs_4_2().build().unwrap();
+    /// assert_eq!(6, rs.total_shard_count());
+    /// ```
+    pub fn build(&self) -> Result<ReedSolomon, Error> {
+        ReedSolomon::new(self.data_shards, self.parity_shards)
+    }
+
+    /// Returns the no. of stored bytes per byte of data, i.e. total shards
+    /// divided by data shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::profiles;
+    ///
+    /// assert_eq!(1.5, profiles::rs_4_2().overhead());
+    /// ```
+    pub fn overhead(&self) -> f64 {
+        (self.data_shards + self.parity_shards) as f64 / self.data_shards as f64
+    }
+
+    /// Create a profile with 256 KiB shards.
+    fn with_shard_len(data_shards: usize, parity_shards: usize) -> Profile {
+        Profile {
+            data_shards,
+            parity_shards,
+            stripe_size: data_shards * PROFILE_SHARD_LEN,
+        }
+    }
+}
+
+/// 4 data shards and 2 parity shards. Survives the loss of any 2 shards
+/// for 1.5x the storage, a good fit for small clusters of 6 nodes.
+///
+/// # Example
+/// ```
+/// use reed_solomon::profiles;
+///
+/// let profile = profiles::rs_4_2();
+/// assert_eq!(1024 * 1024, profile.stripe_size);
+/// ```
+pub fn rs_4_2() -> Profile {
+    Profile::with_shard_len(4, 2)
+}
+
+/// 8 data shards and 3 parity shards. Survives the loss of any 3 shards
+/// for 1.375x the storage, trading a wider stripe for less overhead.
+///
+/// # Example
+/// ```
+/// use reed_solomon::profiles;
+///
+/// let profile = profiles::rs_8_3();
+/// assert_eq!(3, profile.parity_shards);
+/// ```
+pub fn rs_8_3() -> Profile {
+    Profile::with_shard_len(8, 3)
+}
+
+/// 10 data shards and 4 parity shards. Survives the loss of any 4 shards
+/// for 1.4x the storage, the layout of many large object stores. Every
+/// repair of a data shard reads 10 shards, so it needs a fast network.
+///
+/// # Example
+/// ```
+/// use reed_solomon::profiles;
+///
+/// let profile = profiles::rs_10_4();
+/// assert_eq!(4, profile.parity_shards);
+/// ```
+pub fn rs_10_4() -> Profile {
+    Profile::with_shard_len(10, 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::striper::Striper;
+
+    #[test]
+    fn test_profiles_build() {
+        for profile in [rs_4_2(), rs_8_3(), rs_10_4()] {
+            let rs = match profile.build() {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(profile.data_shards, rs.data_shard_count());
+            assert_eq!(profile.parity_shards, rs.parity_shard_count());
+
+            let data = vec![7; profile.stripe_size + 1];
+            let striper = match Striper::new(&rs, &data, profile.stripe_size) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(2, striper.stripe_count());
+            assert_eq!(PROFILE_SHARD_LEN, striper.shard_len());
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+impl ReedSolomon {
+    /// No. of bytes of every shard processed between two calls of the
+    /// progress callback of `encode_with_progress` and `decode_with_progress`,
+    /// and between two checks for cancellation of `decode_cancellable`.
+    pub const PROGRESS_CHUNK_LEN: usize = 1 << 20;
+
+    /// Encodes checksum shards like `encode`, but processes the shards in
+    /// chunks of `PROGRESS_CHUNK_LEN` bytes and calls the given callback
+    /// after every chunk with the no. of bytes of every shard done so far
+    /// and the length of the shards, e.g. to show progress in a UI.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    /// * `on_progress` - Called with the bytes done and the bytes in total of every shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+    /// let encoded_shards = rs.encode_with_progress(shards, |done, total| {
+    ///     println!("{} of {} bytes", done, total);
+    /// });
+    /// ```
+    pub fn encode_with_progress(
+        &self,
+        shards: Vec<Vec<u8>>,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.encode_in_chunks(shards, &mut on_progress, &|| false)
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is
+    /// missing like `decode`, but processes the shards in chunks of
+    /// `PROGRESS_CHUNK_LEN` bytes and calls the given callback after every
+    /// chunk with the no. of bytes of every shard done so far and the
+    /// length of the shards.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `on_progress` - Called with the bytes done and the bytes in total of every shard.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// let decoded_shards = rs.decode_with_progress(shards, |done, total| {
+    ///     println!("{} of {} bytes", done, total);
+    /// });
+    /// ```
+    pub fn decode_with_progress(
+        &self,
+        shards: Vec<Vec<u8>>,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.decode_in_chunks(shards, &mut on_progress, &|| false)
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is
+    /// missing like `decode`, but checks the given flag before every chunk
+    /// of `PROGRESS_CHUNK_LEN` bytes and stops with `Error::Cancelled` as
+    /// soon as it is set, so that a long repair can be aborted promptly.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `should_cancel` - Flag set by another thread to cancel the decode.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::atomic::AtomicBool;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let should_cancel = AtomicBool::new(false);
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// let decoded_shards = rs.decode_cancellable(shards, &should_cancel);
+    /// ```
+    pub fn decode_cancellable(
+        &self,
+        shards: Vec<Vec<u8>>,
+        should_cancel: &AtomicBool,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.decode_in_chunks(shards, &mut |_, _| {}, &|| {
+            should_cancel.load(Ordering::Relaxed)
+        })
+    }
+
+    /// Encodes checksum shards in chunks of `PROGRESS_CHUNK_LEN` bytes,
+    /// calling the progress callback after every chunk and stopping with
+    /// `Error::Cancelled` as soon as `should_cancel` returns true, which is
+    /// checked before every output shard of every chunk.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    /// * `on_progress` - Called with the bytes done and the bytes in total of every shard.
+    /// * `should_cancel` - Returns whether to stop.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]];
+    /// let encoded_shards = rs.encode_in_chunks(shards, &mut |_, _| {}, &|| false);
+    /// ```
+    pub(crate) fn encode_in_chunks(
+        &self,
+        shards: Vec<Vec<u8>>,
+        on_progress: &mut dyn FnMut(usize, usize),
+        should_cancel: &dyn Fn() -> bool,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.check_shard_sizes(&shards)?;
+        let shard_elem_len = shards[0].len();
+
+        // A non-systematic code overwrites every shard, so it reads a
+        // copy of the data shards.
+        let mut shards = shards;
+        let copied: Vec<Vec<u8>>;
+        let (rows, inputs, outputs) = if self.systematic {
+            let (inputs, outputs) = shards.split_at_mut(self.data_shard_count);
+            (&self.parity, &*inputs, outputs)
+        } else {
+            copied = shards[..self.data_shard_count].to_vec();
+            (&self.matrix, &copied[..], &mut shards[..])
+        };
+        for start in (0..shard_elem_len).step_by(Self::PROGRESS_CHUNK_LEN) {
+            let end = (start + Self::PROGRESS_CHUNK_LEN).min(shard_elem_len);
+            let chunk: Vec<&[u8]> = inputs.iter().map(|shard| &shard[start..end]).collect();
+            for (row, output) in rows.data.iter().zip(outputs.iter_mut()) {
+                if should_cancel() {
+                    return Err(Error::Cancelled);
+                }
+                self.combine_row(row, &chunk, &mut output[start..end]);
+            }
+            on_progress(end, shard_elem_len);
+        }
+
+        Ok(shards)
+    }
+
+    /// Recover the missing shards in chunks of `PROGRESS_CHUNK_LEN` bytes,
+    /// calling the progress callback after every chunk and stopping with
+    /// `Error::Cancelled` as soon as `should_cancel` returns true, which is
+    /// checked before every chunk.
+    /// # Arguments
+    ///
+    /// * `shards` - Given shards including data and parity shards. Some shards might be missing.
+    /// * `on_progress` - Called with the bytes done and the bytes in total of every shard.
+    /// * `should_cancel` - Returns whether to stop.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+    /// let decoded_shards = rs.decode_in_chunks(shards, &mut |_, _| {}, &|| false);
+    /// ```
+    pub(crate) fn decode_in_chunks(
+        &self,
+        shards: Vec<Vec<u8>>,
+        on_progress: &mut dyn FnMut(usize, usize),
+        should_cancel: &dyn Fn() -> bool,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let (_, shard_elem_len) = self.check_shard_sizes_for_decode(&shards)?;
+        let missing: Vec<usize> = (0..self.total_shard_count)
+            .filter(|&i| shards[i].is_empty())
+            .collect();
+
+        // Every chunk is decoded on its own. The inverted matrix is taken
+        // from the decode cache after the first chunk.
+        let mut outputs: Vec<Vec<u8>> = vec![Vec::with_capacity(shard_elem_len); missing.len()];
+        for start in (0..shard_elem_len).step_by(Self::PROGRESS_CHUNK_LEN) {
+            if should_cancel() {
+                return Err(Error::Cancelled);
+            }
+            let end = (start + Self::PROGRESS_CHUNK_LEN).min(shard_elem_len);
+            let mut chunk: Vec<Option<Cow<[u8]>>> = shards
+                .iter()
+                .map(|shard| (!shard.is_empty()).then(|| Cow::Borrowed(&shard[start..end])))
+                .collect();
+            self.reconstruct(&mut chunk)?;
+            for (&i, output) in missing.iter().zip(outputs.iter_mut()) {
+                if let Some(part) = &chunk[i] {
+                    output.extend_from_slice(part);
+                }
+            }
+            on_progress(end, shard_elem_len);
+        }
+
+        let mut shards = shards;
+        for (&i, output) in missing.iter().zip(outputs) {
+            shards[i] = output;
+        }
+
+        Ok(shards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReedSolomonBuilder;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_progress_round_trip() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            // Two full chunks and a partial one.
+            let shard_elem_len = 2 * ReedSolomon::PROGRESS_CHUNK_LEN + 5;
+            let mut shards: Vec<Vec<u8>> = (0..3)
+                .map(|i| (0..shard_elem_len).map(|j| (i * 7 + j) as u8).collect())
+                .collect();
+            shards.resize(5, vec![0; shard_elem_len]);
+            let encoded = match rs.encode(shards.clone()) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut calls: Vec<(usize, usize)> = vec![];
+            match rs.encode_with_progress(shards, |done, total| calls.push((done, total))) {
+                Ok(x) => assert_eq!(encoded, x),
+                Err(e) => panic!("{}", e),
+            }
+            let expected = vec![
+                (ReedSolomon::PROGRESS_CHUNK_LEN, shard_elem_len),
+                (2 * ReedSolomon::PROGRESS_CHUNK_LEN, shard_elem_len),
+                (shard_elem_len, shard_elem_len),
+            ];
+            assert_eq!(expected, calls);
+
+            let mut broken = encoded.clone();
+            broken[0] = vec![];
+            broken[4] = vec![];
+            let mut calls: Vec<(usize, usize)> = vec![];
+            match rs.decode_with_progress(broken, |done, total| calls.push((done, total))) {
+                Ok(x) => assert_eq!(encoded, x),
+                Err(e) => panic!("{}", e),
+            }
+            assert_eq!(expected, calls);
+        }
+    }
+
+    #[test]
+    fn test_decode_cancellable() {
+        let rs = match ReedSolomon::new(2, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+        let should_cancel = AtomicBool::new(true);
+        assert!(matches!(
+            rs.decode_cancellable(shards.clone(), &should_cancel),
+            Err(Error::Cancelled)
+        ));
+
+        should_cancel.store(false, Ordering::Relaxed);
+        match rs.decode_cancellable(shards.clone(), &should_cancel) {
+            Ok(x) => assert_eq!(rs.decode(shards).ok(), Some(x)),
+            Err(e) => panic!("{}", e),
+        }
+
+        // Cancelling from the progress callback stops before the next chunk.
+        let shard_elem_len = 3 * ReedSolomon::PROGRESS_CHUNK_LEN;
+        let shards = vec![
+            vec![1; shard_elem_len],
+            vec![],
+            vec![2; shard_elem_len],
+            vec![],
+        ];
+        let should_cancel = AtomicBool::new(false);
+        let mut chunks = 0;
+        let result = rs.decode_in_chunks(
+            shards,
+            &mut |_, _| {
+                chunks += 1;
+                should_cancel.store(true, Ordering::Relaxed);
+            },
+            &|| should_cancel.load(Ordering::Relaxed),
+        );
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(1, chunks);
+    }
+}
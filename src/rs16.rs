@@ -0,0 +1,404 @@
+use crate::error::Error;
+use crate::galois::{GaloisField16, FIELD_SIZE_16};
+
+/// A Struct to represent and store data for Reed Solomon Erasure Coding
+/// over GF(2^16), which supports up to 65536 total shards instead of the
+/// 256 of `ReedSolomon`. The shards are made of 16 bit little endian
+/// words, so their length must be even. The parity shards are generated
+/// by a Cauchy matrix, which is MDS by construction, as inverting the
+/// top square of a Vandermonde matrix of that size would be too slow.
+#[derive(Clone)]
+pub struct ReedSolomon16 {
+    data_shard_count: usize,
+    parity_shard_count: usize,
+    total_shard_count: usize,
+    gf: GaloisField16,
+    parity: Vec<Vec<u16>>,
+}
+
+impl ReedSolomon16 {
+    /// Create a new Reed Solomon Erasure Coding to be used to encode data.
+    /// # Arguments
+    ///
+    /// * `data_shards` - No. of Data Shards
+    /// * `parity_shards` - No. of Parity Shards i.e. Checksum Shards
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(512, 64);
+    /// ```
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<ReedSolomon16, Error> {
+        if data_shards == 0 {
+            return Err(Error::ZeroDataShards);
+        }
+        if parity_shards == 0 {
+            return Err(Error::ZeroParityShards);
+        }
+        let total_shards = match data_shards.checked_add(parity_shards) {
+            Some(x) => x,
+            None => return Err(Error::ShardsOverflow),
+        };
+        // Every row and every column of the Cauchy matrix needs its own
+        // element of the field.
+        if total_shards > FIELD_SIZE_16 {
+            return Err(Error::TooManyShardsForField(total_shards, FIELD_SIZE_16));
+        }
+
+        // The element at row r and column c is 1 / (x_r + y_c) with
+        // x_r = data_shards + r and y_c = c.
+        let gf = GaloisField16::new();
+        let parity: Vec<Vec<u16>> = (data_shards..total_shards)
+            .map(|x| {
+                (0..data_shards)
+                    .map(|y| gf.div(1, x as u16 ^ y as u16))
+                    .collect()
+            })
+            .collect();
+
+        Ok(ReedSolomon16 {
+            data_shard_count: data_shards,
+            parity_shard_count: parity_shards,
+            total_shard_count: total_shards,
+            gf,
+            parity,
+        })
+    }
+
+    /// Returns the no. of data shards of the code.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(512, 64).unwrap();
+    /// assert_eq!(512, rs.data_shard_count());
+    /// ```
+    pub fn data_shard_count(&self) -> usize {
+        self.data_shard_count
+    }
+
+    /// Returns the no. of parity shards of the code.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(512, 64).unwrap();
+    /// assert_eq!(64, rs.parity_shard_count());
+    /// ```
+    pub fn parity_shard_count(&self) -> usize {
+        self.parity_shard_count
+    }
+
+    /// Returns the total no. of shards of the code.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(512, 64).unwrap();
+    /// assert_eq!(576, rs.total_shard_count());
+    /// ```
+    pub fn total_shard_count(&self) -> usize {
+        self.total_shard_count
+    }
+
+    /// Encodes checksum shards for a set of data shards.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Parity shards will be overwritten.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(2, 2).unwrap();
+    /// let shards = rs.encode(vec![vec![0, 1], vec![2, 3], vec![0; 2], vec![0; 2]]).unwrap();
+    /// ```
+    pub fn encode(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        if shards.len() != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let lens: Vec<Option<usize>> = shards.iter().map(|shard| Some(shard.len())).collect();
+        self.check_lens(&lens)?;
+
+        let mut shards = shards;
+        let (inputs, outputs) = shards.split_at_mut(self.data_shard_count);
+        for (row, output) in self.parity.iter().zip(outputs.iter_mut()) {
+            self.combine_row(row, inputs, output);
+        }
+
+        Ok(shards)
+    }
+
+    /// Check whether the parity shards match the data shards. The parity
+    /// is computed into a scratch buffer one shard at a time.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(2, 2).unwrap();
+    /// let shards = rs.encode(vec![vec![0, 1], vec![2, 3], vec![0; 2], vec![0; 2]]).unwrap();
+    /// assert!(rs.verify(&shards).unwrap());
+    /// ```
+    pub fn verify(&self, shards: &[Vec<u8>]) -> Result<bool, Error> {
+        if shards.len() != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let lens: Vec<Option<usize>> = shards.iter().map(|shard| Some(shard.len())).collect();
+        let shard_elem_len = self.check_lens(&lens)?;
+
+        let (inputs, outputs) = shards.split_at(self.data_shard_count);
+        let mut expected: Vec<u8> = vec![0; shard_elem_len];
+        for (row, output) in self.parity.iter().zip(outputs.iter()) {
+            self.combine_row(row, inputs, &mut expected);
+            if expected != *output {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Recover the missing shards in place. Only a system of the size of
+    /// the no. of missing data shards is inverted, so it stays cheap for
+    /// codes with thousands of data shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(2, 2).unwrap();
+    /// let shards = rs.encode(vec![vec![0, 1], vec![2, 3], vec![0; 2], vec![0; 2]]).unwrap();
+    /// let mut broken: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+    /// broken[0] = None;
+    /// broken[3] = None;
+    /// rs.reconstruct(&mut broken).unwrap();
+    /// assert_eq!(Some(vec![0, 1]), broken[0]);
+    /// ```
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        if shards.len() != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let lens: Vec<Option<usize>> = shards
+            .iter()
+            .map(|shard| shard.as_ref().map(|x| x.len()))
+            .collect();
+        let shard_elem_len = self.check_lens(&lens)?;
+
+        let present: Vec<usize> = (0..self.total_shard_count)
+            .filter(|&i| shards[i].is_some())
+            .collect();
+        if present.len() == self.total_shard_count {
+            return Ok(());
+        }
+        if present.len() < self.data_shard_count {
+            return Err(Error::TooFewShards);
+        }
+
+        // Only the missing data shards are unknown, so they are solved
+        // from as many present parity shards. Subtracting the present data
+        // shards from those leaves the sum of the missing data shards
+        // times their coefficients in every one of them, which keeps the
+        // system as small as the no. of missing data shards.
+        let missing: Vec<usize> = (0..self.data_shard_count)
+            .filter(|&i| shards[i].is_none())
+            .collect();
+        let used: Vec<usize> = (self.data_shard_count..self.total_shard_count)
+            .filter(|&i| shards[i].is_some())
+            .take(missing.len())
+            .collect();
+        if !missing.is_empty() {
+            let mut syndromes: Vec<Vec<u8>> = Vec::with_capacity(used.len());
+            for &i in used.iter() {
+                let mut syndrome = shards[i].clone().unwrap_or_default();
+                let row = &self.parity[i - self.data_shard_count];
+                for (&coefficient, shard) in row.iter().zip(shards.iter()) {
+                    if let Some(shard) = shard {
+                        self.gf.mul_add_words(coefficient, shard, &mut syndrome);
+                    }
+                }
+                syndromes.push(syndrome);
+            }
+
+            let coefficients: Vec<Vec<u16>> = used
+                .iter()
+                .map(|&i| {
+                    let row = &self.parity[i - self.data_shard_count];
+                    missing.iter().map(|&c| row[c]).collect()
+                })
+                .collect();
+            let inverse = match self.gf.invert_matrix(coefficients) {
+                Some(x) => x,
+                None => return Err(Error::SingularMatrix),
+            };
+            for (&i, row) in missing.iter().zip(inverse.iter()) {
+                let mut shard: Vec<u8> = vec![0; shard_elem_len];
+                for (&coefficient, syndrome) in row.iter().zip(syndromes.iter()) {
+                    self.gf.mul_add_words(coefficient, syndrome, &mut shard);
+                }
+                shards[i] = Some(shard);
+            }
+        }
+
+        // Filling the missing parity shards from the complete data.
+        let (data, parity) = shards.split_at_mut(self.data_shard_count);
+        let data: Vec<&[u8]> = data.iter().map(|x| x.as_deref().unwrap_or(&[])).collect();
+        for (row, shard) in self.parity.iter().zip(parity.iter_mut()) {
+            if shard.is_none() {
+                let mut output: Vec<u8> = vec![0; shard_elem_len];
+                self.combine_row(row, &data, &mut output);
+                *shard = Some(output);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Takes shards as input and recover any data or parity shards that is
+    /// missing. Missing shards are empty.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are empty.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ReedSolomon16;
+    ///
+    /// let rs = ReedSolomon16::new(2, 2).unwrap();
+    /// let shards = rs.encode(vec![vec![0, 1], vec![2, 3], vec![0; 2], vec![0; 2]]).unwrap();
+    /// let mut broken = shards.clone();
+    /// broken[1] = vec![];
+    /// assert_eq!(shards, rs.decode(broken).unwrap());
+    /// ```
+    pub fn decode(&self, shards: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let mut shards: Vec<Option<Vec<u8>>> = shards
+            .into_iter()
+            .map(|shard| if shard.is_empty() { None } else { Some(shard) })
+            .collect();
+        self.reconstruct(&mut shards)?;
+
+        Ok(shards.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    /// Computes the linear combination of the given shards with the given
+    /// coefficients into the output, which is overwritten.
+    fn combine_row<T: AsRef<[u8]>>(&self, row: &[u16], inputs: &[T], output: &mut [u8]) {
+        output.fill(0);
+        for (&coefficient, input) in row.iter().zip(inputs.iter()) {
+            self.gf.mul_add_words(coefficient, input.as_ref(), output);
+        }
+    }
+
+    /// Check that the present shards are non empty and have the same even
+    /// length. Returns the length of the shards.
+    fn check_lens(&self, lens: &[Option<usize>]) -> Result<usize, Error> {
+        let shard_elem_len = match lens.iter().flatten().next() {
+            Some(&x) => x,
+            None => return Err(Error::TooFewShards),
+        };
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if lens.iter().flatten().any(|&len| len != shard_elem_len) {
+            return Err(Error::InconsistentShards);
+        }
+        if shard_elem_len % 2 != 0 {
+            return Err(Error::OddShardLength(shard_elem_len));
+        }
+
+        Ok(shard_elem_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let rs = match ReedSolomon16::new(300, 20) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards: Vec<Vec<u8>> = (0..300)
+            .map(|i| (0..4).map(|j| (i * 7 + j * 13) as u8).collect())
+            .collect();
+        shards.resize(320, vec![0; 4]);
+        let encoded = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        match rs.verify(&encoded) {
+            Ok(x) => assert!(x),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut broken = encoded.clone();
+        for i in [0, 1, 150, 299, 300, 319] {
+            broken[i] = vec![];
+        }
+        match rs.decode(broken) {
+            Ok(x) => assert_eq!(encoded, x),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut corrupted = encoded.clone();
+        corrupted[10][1] ^= 1;
+        match rs.verify(&corrupted) {
+            Ok(x) => assert!(!x),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut broken: Vec<Option<Vec<u8>>> = encoded.iter().cloned().map(Some).collect();
+        for i in [7, 301, 302, 318] {
+            broken[i] = None;
+        }
+        match rs.reconstruct(&mut broken) {
+            Ok(_) => assert_eq!(encoded, broken.into_iter().flatten().collect::<Vec<_>>()),
+            Err(e) => panic!("{}", e),
+        }
+
+        assert!(ReedSolomon16::new(65000, 537).is_err());
+        assert!(matches!(
+            rs.encode(vec![vec![0; 3]; 320]),
+            Err(Error::OddShardLength(3))
+        ));
+    }
+
+    #[test]
+    fn test_reconstruct_many_data_shards() {
+        let rs = match ReedSolomon16::new(20000, 4) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let mut shards: Vec<Vec<u8>> = (0..20000u32)
+            .map(|i| (i * 31).to_le_bytes().to_vec())
+            .collect();
+        shards.resize(20004, vec![0; 4]);
+        let encoded = match rs.encode(shards) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let mut broken: Vec<Option<Vec<u8>>> = encoded.iter().cloned().map(Some).collect();
+        for i in [0, 9999, 19999, 20002] {
+            broken[i] = None;
+        }
+        match rs.reconstruct(&mut broken) {
+            Ok(_) => assert_eq!(encoded, broken.into_iter().flatten().collect::<Vec<_>>()),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
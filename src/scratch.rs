@@ -0,0 +1,208 @@
+use crate::error::Error;
+use crate::matrix::Matrix;
+use crate::ReedSolomon;
+
+/// A Struct to hold the working memory of `ReedSolomon::reconstruct_with`
+/// between calls. The decoding rows of the last erasure pattern are kept,
+/// so that repairing the same pattern again, as a repair loop does, needs
+/// no heap allocation at all.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeScratch {
+    // The encoding matrix of the code the rows were built for.
+    matrix: Option<Matrix>,
+    present: Vec<bool>,
+    sub_matrix_rows: Vec<usize>,
+    missing: Vec<usize>,
+    rows: Vec<Vec<u8>>,
+}
+
+impl DecodeScratch {
+    /// Create a new empty DecodeScratch.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::scratch::DecodeScratch;
+    ///
+    /// let scratch = DecodeScratch::new();
+    /// ```
+    pub fn new() -> DecodeScratch {
+        DecodeScratch::default()
+    }
+
+    /// Build the decoding rows of the given erasure pattern, unless they
+    /// were already built for it and the given code.
+    /// # Arguments
+    ///
+    /// * `rs` - Code the shards were encoded with
+    /// * `present` - Whether each shard is present, one entry per shard
+    fn prepare(&mut self, rs: &ReedSolomon, present: &[bool]) -> Result<(), Error> {
+        if self.present == present && self.matrix.as_ref() == Some(&rs.matrix) {
+            return Ok(());
+        }
+
+        let sub_matrix_rows: Vec<usize> = (0..rs.total_shard_count)
+            .filter(|&i| present[i])
+            .take(rs.data_shard_count)
+            .collect();
+        let missing: Vec<usize> = (0..rs.total_shard_count).filter(|&i| !present[i]).collect();
+        let data_decode_matrix = rs.decode_matrix(&sub_matrix_rows)?;
+        let rows = rs
+            .matrix
+            .submatrix_by_rows(&missing)?
+            .mul(data_decode_matrix, &rs.gf)?;
+
+        // Only filled in once every step succeeded, so that a failed
+        // call doesn't leave rows for the wrong pattern behind.
+        self.matrix = Some(rs.matrix.clone());
+        self.present = present.to_vec();
+        self.sub_matrix_rows = sub_matrix_rows;
+        self.missing = missing;
+        self.rows = rows.into_data();
+
+        Ok(())
+    }
+}
+
+impl ReedSolomon {
+    /// Takes shards as input, with a buffer of the shard length for every
+    /// missing shard, and recover the missing data or parity shards into
+    /// those buffers. Nothing is allocated when the scratch was last used
+    /// for the same erasure pattern and code, and the default kernel is
+    /// used, so steady-state repair is allocation free.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards will be overwritten.
+    /// * `present` - Whether each shard is present, one entry per shard.
+    /// * `scratch` - Working memory reused across calls.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::scratch::DecodeScratch;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut scratch = DecodeScratch::new();
+    /// let mut shards = vec![vec![0, 1, 2], vec![0; 3], vec![0; 3], vec![5, 14, 11]];
+    /// rs.reconstruct_with(&mut shards, &[true, false, false, true], &mut scratch).unwrap();
+    /// assert_eq!(vec![3, 4, 5], shards[1]);
+    /// ```
+    pub fn reconstruct_with<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        shards: &mut [T],
+        present: &[bool],
+        scratch: &mut DecodeScratch,
+    ) -> Result<(), Error> {
+        self.check_shard_sizes(shards)?;
+        if present.len() != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        if present.iter().filter(|&&x| x).count() < self.data_shard_count {
+            return Err(Error::TooFewShards);
+        }
+        if present.iter().all(|&x| x) {
+            return Ok(());
+        }
+
+        scratch.prepare(self, present)?;
+        for (&i, row) in scratch.missing.iter().zip(scratch.rows.iter()) {
+            // Split the output off the shards, so that the present
+            // shards can be read while it is written.
+            let (before, rest) = shards.split_at_mut(i);
+            let (output, after) = rest.split_at_mut(1);
+            let output = output[0].as_mut();
+            let input = |j: usize| {
+                if j < i {
+                    before[j].as_ref()
+                } else {
+                    after[j - i - 1].as_ref()
+                }
+            };
+
+            let mut terms = row.iter().zip(scratch.sub_matrix_rows.iter());
+            if let Some((&coeff, &j)) = terms.next() {
+                self.kernel.mul_slice(coeff, input(j), output);
+            }
+            for (&coeff, &j) in terms {
+                self.kernel.mul_slice_add(coeff, input(j), output);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReedSolomonBuilder;
+
+    #[test]
+    fn test_reconstruct_with() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(5, vec![0; 3]);
+            let encoded = match rs.encode(shards) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let mut scratch = DecodeScratch::new();
+            for present in [
+                [false, true, false, true, true],
+                [false, true, false, true, true],
+                [true, true, true, false, false],
+                [true, false, true, true, false],
+            ] {
+                let mut broken = encoded.clone();
+                for (shard, &x) in broken.iter_mut().zip(present.iter()) {
+                    if !x {
+                        shard.fill(0xFF);
+                    }
+                }
+                match rs.reconstruct_with(&mut broken, &present, &mut scratch) {
+                    Ok(_) => assert_eq!(encoded, broken),
+                    Err(e) => panic!("{}", e),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_with_other_code() {
+        let rs = match ReedSolomon::new(2, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let other = match ReedSolomonBuilder::new(2, 2).systematic(false).build() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let present = [true, false, false, true];
+        let mut scratch = DecodeScratch::new();
+        for code in [&rs, &other] {
+            let encoded =
+                match code.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]) {
+                    Ok(x) => x,
+                    Err(e) => panic!("{}", e),
+                };
+            let mut broken = encoded.clone();
+            broken[1].fill(0);
+            broken[2].fill(0);
+            match code.reconstruct_with(&mut broken, &present, &mut scratch) {
+                Ok(_) => assert_eq!(encoded, broken),
+                Err(e) => panic!("{}", e),
+            }
+        }
+
+        let mut shards = vec![vec![0; 3]; 4];
+        assert!(matches!(
+            rs.reconstruct_with(&mut shards, &[true, false, false, false], &mut scratch),
+            Err(Error::TooFewShards)
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+use bytes::{Bytes, BytesMut};
+
+impl ReedSolomon {
+    /// Encodes checksum shards for data shards held in `Bytes`, e.g.
+    /// buffers received from the network, without copying them. Only the
+    /// new shards are allocated, the data shards of a systematic code are
+    /// returned as clones of the given `Bytes`.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards, all of the same length.
+    ///
+    /// # Example
+    /// ```
+    /// use bytes::Bytes;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = [Bytes::from_static(&[0, 1, 2]), Bytes::from_static(&[3, 4, 5])];
+    /// let shards = rs.encode_bytes(&data).unwrap();
+    /// assert_eq!(&[5, 14, 11], &shards[3][..]);
+    /// ```
+    pub fn encode_bytes(&self, data: &[Bytes]) -> Result<Vec<Bytes>, Error> {
+        if data.len() != self.data_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let shard_elem_len = data[0].len();
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if data.iter().any(|shard| shard.len() != shard_elem_len) {
+            return Err(Error::InconsistentShards);
+        }
+
+        // Every shard of a non-systematic code is a combination of all
+        // data shards, so all of them are new.
+        let (rows, mut shards) = if self.systematic {
+            (&self.parity, data.to_vec())
+        } else {
+            (&self.matrix, vec![])
+        };
+        let mut outputs: Vec<BytesMut> = vec![BytesMut::zeroed(shard_elem_len); rows.data.len()];
+        self.encode_shards(rows, data, &mut outputs);
+        shards.extend(outputs.into_iter().map(BytesMut::freeze));
+
+        Ok(shards)
+    }
+
+    /// Takes shards held in `Bytes` as input and recover any data or parity
+    /// shards that is missing. The present shards are returned as they
+    /// are and the recovered ones are moved into `Bytes` without a copy.
+    /// Returns all the shards including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use bytes::Bytes;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let shards = vec![Some(Bytes::from_static(&[0, 1, 2])), None, None, Some(Bytes::from_static(&[5, 14, 11]))];
+    /// let decoded = rs.decode_bytes(shards).unwrap();
+    /// assert_eq!(&[3, 4, 5], &decoded[1][..]);
+    /// ```
+    pub fn decode_bytes(&self, shards: Vec<Option<Bytes>>) -> Result<Vec<Bytes>, Error> {
+        let mut shards = shards;
+        self.reconstruct(&mut shards)?;
+
+        Ok(shards.into_iter().map(Option::unwrap_or_default).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReedSolomonBuilder;
+
+    #[test]
+    fn test_encode_decode_bytes() {
+        for systematic in [true, false] {
+            let result = ReedSolomonBuilder::new(3, 2).systematic(systematic).build();
+            let rs = match result {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut shards = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+            shards.resize(5, vec![0; 3]);
+            let encoded = match rs.encode(shards.clone()) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+
+            let data: Vec<Bytes> = shards[..3].iter().cloned().map(Bytes::from).collect();
+            let encoded_bytes = match rs.encode_bytes(&data) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            assert_eq!(encoded, encoded_bytes);
+            if systematic {
+                // The data shards are the given buffers, not copies.
+                assert_eq!(data[0].as_ptr(), encoded_bytes[0].as_ptr());
+            }
+
+            let mut broken: Vec<Option<Bytes>> = encoded_bytes.into_iter().map(Some).collect();
+            broken[1] = None;
+            broken[3] = None;
+            match rs.decode_bytes(broken) {
+                Ok(x) => assert_eq!(encoded, x),
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use crate::error::Error;
+use crate::ReedSolomon;
+
+/// A Struct to hold all the shards of a code along with whether each of
+/// them is present, so that a missing shard is marked as such instead of
+/// being an empty vector.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShardSet {
+    shards: Vec<Vec<u8>>,
+    present: Vec<bool>,
+}
+
+impl ShardSet {
+    /// Create a new ShardSet from the given shards, where every non-empty
+    /// shard is present, like the shards taken by `decode`.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are empty.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]]);
+    /// assert_eq!(2, set.present_count());
+    /// ```
+    pub fn new(shards: Vec<Vec<u8>>) -> ShardSet {
+        let present = shards.iter().map(|shard| !shard.is_empty()).collect();
+
+        ShardSet { shards, present }
+    }
+
+    /// Create a new ShardSet from the given shards, where every `Some`
+    /// shard is present, like the shards taken by `reconstruct`.
+    /// # Arguments
+    ///
+    /// * `shards` - All shards including data and parity shards. Missing shards are `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::from_options(vec![Some(vec![0, 1, 2]), None]);
+    /// assert_eq!(vec![1], set.missing_indices());
+    /// ```
+    pub fn from_options(shards: Vec<Option<Vec<u8>>>) -> ShardSet {
+        let present = shards.iter().map(Option::is_some).collect();
+        let shards = shards
+            .into_iter()
+            .map(|shard| shard.unwrap_or_default())
+            .collect();
+
+        ShardSet { shards, present }
+    }
+
+    /// Returns the total no. of shards, present or missing.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![]]);
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns whether there are no shards at all.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// assert!(ShardSet::new(vec![]).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.shards.is_empty()
+    }
+
+    /// Returns whether the shard at the given index is present. An index
+    /// out of bounds is never present.
+    /// # Arguments
+    ///
+    /// * `index` - Index of the shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![]]);
+    /// assert!(set.is_present(0));
+    /// assert!(!set.is_present(1));
+    /// ```
+    pub fn is_present(&self, index: usize) -> bool {
+        self.present.get(index).copied().unwrap_or(false)
+    }
+
+    /// Returns whether each shard is present, one entry per shard, as
+    /// taken by `ReedSolomon::is_shard_recoverable` and friends.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![]]);
+    /// assert_eq!(&[true, false], set.present());
+    /// ```
+    pub fn present(&self) -> &[bool] {
+        &self.present
+    }
+
+    /// Returns the no. of present shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![], vec![6, 11, 12]]);
+    /// assert_eq!(2, set.present_count());
+    /// ```
+    pub fn present_count(&self) -> usize {
+        self.present.iter().filter(|&&present| present).count()
+    }
+
+    /// Returns the indices of the missing shards in order.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]]);
+    /// assert_eq!(vec![1, 3], set.missing_indices());
+    /// ```
+    pub fn missing_indices(&self) -> Vec<usize> {
+        self.present
+            .iter()
+            .enumerate()
+            .filter(|(_, &present)| !present)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the shard at the given index if it is present.
+    /// # Arguments
+    ///
+    /// * `index` - Index of the shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![]]);
+    /// assert_eq!(Some(&[0, 1, 2][..]), set.get(0));
+    /// assert_eq!(None, set.get(1));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if self.is_present(index) {
+            Some(&self.shards[index])
+        } else {
+            None
+        }
+    }
+
+    /// Mark the shard at the given index as missing, e.g. after its
+    /// checksum didn't match, and drop its contents.
+    /// # Arguments
+    ///
+    /// * `index` - Index of the shard
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let mut set = ShardSet::new(vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    /// set.mark_missing(1).unwrap();
+    /// assert_eq!(vec![1], set.missing_indices());
+    /// ```
+    pub fn mark_missing(&mut self, index: usize) -> Result<(), Error> {
+        if index >= self.shards.len() {
+            return Err(Error::InvalidShardIndex(index));
+        }
+        self.present[index] = false;
+        self.shards[index] = vec![];
+
+        Ok(())
+    }
+
+    /// Returns the shards, with an empty shard for every missing one, as
+    /// taken by `ReedSolomon::decode`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::from_options(vec![Some(vec![0, 1, 2]), None]);
+    /// assert_eq!(vec![vec![0, 1, 2], vec![]], set.into_shards());
+    /// ```
+    pub fn into_shards(self) -> Vec<Vec<u8>> {
+        self.shards
+    }
+
+    /// Returns the shards, with `None` for every missing one, as taken by
+    /// `ReedSolomon::reconstruct`.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::ShardSet;
+    ///
+    /// let set = ShardSet::new(vec![vec![0, 1, 2], vec![]]);
+    /// assert_eq!(vec![Some(vec![0, 1, 2]), None], set.into_options());
+    /// ```
+    pub fn into_options(self) -> Vec<Option<Vec<u8>>> {
+        self.shards
+            .into_iter()
+            .zip(self.present)
+            .map(|(shard, present)| present.then_some(shard))
+            .collect()
+    }
+}
+
+impl From<Vec<Vec<u8>>> for ShardSet {
+    fn from(shards: Vec<Vec<u8>>) -> Self {
+        ShardSet::new(shards)
+    }
+}
+
+impl From<Vec<Option<Vec<u8>>>> for ShardSet {
+    fn from(shards: Vec<Option<Vec<u8>>>) -> Self {
+        ShardSet::from_options(shards)
+    }
+}
+
+impl From<ShardSet> for Vec<Vec<u8>> {
+    fn from(set: ShardSet) -> Self {
+        set.into_shards()
+    }
+}
+
+impl From<ShardSet> for Vec<Option<Vec<u8>>> {
+    fn from(set: ShardSet) -> Self {
+        set.into_options()
+    }
+}
+
+impl ReedSolomon {
+    /// Takes a ShardSet as input and recover every missing data or parity
+    /// shard in place, so that every shard of the set is present after.
+    /// Fails with `Error::WrongNoOfShards` if the set doesn't hold total
+    /// shard count shards and with `Error::TooFewShards` if fewer than
+    /// data shard count shards are present, leaving the set unchanged.
+    /// # Arguments
+    ///
+    /// * `set` - All shards including data and parity shards.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::{ReedSolomon, ShardSet};
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let mut set = ShardSet::new(vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]]);
+    /// rs.reconstruct_set(&mut set).unwrap();
+    /// assert_eq!(Some(&[3, 4, 5][..]), set.get(1));
+    /// ```
+    pub fn reconstruct_set(&self, set: &mut ShardSet) -> Result<(), Error> {
+        if set.len() != self.total_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        if set.present_count() < self.data_shard_count {
+            return Err(Error::TooFewShards);
+        }
+
+        let mut shards = std::mem::take(set).into_options();
+        let result = self.reconstruct(&mut shards);
+        *set = ShardSet::from_options(shards);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_set_conversions() {
+        let shards = vec![vec![0, 1, 2], vec![], vec![6, 11, 12], vec![]];
+        let set = ShardSet::from(shards.clone());
+        assert_eq!(vec![true, false, true, false], set.present());
+        assert_eq!(2, set.present_count());
+        assert_eq!(vec![1, 3], set.missing_indices());
+
+        let options = set.clone().into_options();
+        assert_eq!(
+            vec![Some(vec![0, 1, 2]), None, Some(vec![6, 11, 12]), None],
+            options
+        );
+        assert_eq!(set, ShardSet::from(options));
+        assert_eq!(shards, Vec::<Vec<u8>>::from(set));
+    }
+
+    #[test]
+    fn test_mark_missing() {
+        let mut set = ShardSet::new(vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        match set.mark_missing(0) {
+            Ok(_) => {}
+            Err(e) => panic!("{}", e),
+        }
+        assert_eq!(None, set.get(0));
+        assert_eq!(vec![0], set.missing_indices());
+        match set.mark_missing(2) {
+            Err(Error::InvalidShardIndex(2)) => {}
+            _ => panic!("An index out of bounds must be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_set() {
+        let rs = match ReedSolomon::new(2, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let encoded = match rs.encode(vec![vec![0, 1, 2], vec![3, 4, 5], vec![0; 3], vec![0; 3]]) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+
+        let mut set = ShardSet::new(encoded.clone());
+        for i in [0, 3] {
+            match set.mark_missing(i) {
+                Ok(_) => {}
+                Err(e) => panic!("{}", e),
+            }
+        }
+        match rs.reconstruct_set(&mut set) {
+            Ok(_) => assert_eq!(encoded, set.into_shards()),
+            Err(e) => panic!("{}", e),
+        }
+
+        let mut set = ShardSet::new(vec![encoded[0].clone(), vec![], vec![], vec![]]);
+        match rs.reconstruct_set(&mut set) {
+            Err(Error::TooFewShards) => assert_eq!(vec![1, 2, 3], set.missing_indices()),
+            _ => panic!("Too few present shards must be rejected"),
+        }
+        let mut set = ShardSet::new(encoded[..3].to_vec());
+        match rs.reconstruct_set(&mut set) {
+            Err(Error::WrongNoOfShards) => {}
+            _ => panic!("A wrong no. of shards must be rejected"),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::error::Error;
+use crate::{ReedSolomon, Stripe};
+
+/// A Struct to encode a large buffer as many stripes, every stripe
+/// holding a fixed no. of bytes of the buffer in its data shards. The
+/// shards of all stripes have the same length, the last stripe is padded
+/// with zeros.
+pub struct Striper<'a> {
+    rs: &'a ReedSolomon,
+    data: &'a [u8],
+    stripe_size: usize,
+    shard_elem_len: usize,
+}
+
+impl<'a> Striper<'a> {
+    /// Create a new Striper for the given buffer.
+    /// # Arguments
+    ///
+    /// * `rs` - Code every stripe is encoded with
+    /// * `data` - Bytes to be encoded
+    /// * `stripe_size` - No. of bytes of the buffer held by every stripe
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::striper::Striper;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = [0, 1, 2, 3, 4, 5, 6];
+    /// let striper = Striper::new(&rs, &data, 4).unwrap();
+    /// assert_eq!(2, striper.stripe_count());
+    /// ```
+    pub fn new(
+        rs: &'a ReedSolomon,
+        data: &'a [u8],
+        stripe_size: usize,
+    ) -> Result<Striper<'a>, Error> {
+        if stripe_size == 0 {
+            return Err(Error::ZeroStripeSize);
+        }
+
+        Ok(Striper {
+            rs,
+            data,
+            stripe_size,
+            shard_elem_len: stripe_size.div_ceil(rs.data_shard_count),
+        })
+    }
+
+    /// Returns the no. of stripes the buffer is split into.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::striper::Striper;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let striper = Striper::new(&rs, &[0; 10], 4).unwrap();
+    /// assert_eq!(3, striper.stripe_count());
+    /// ```
+    pub fn stripe_count(&self) -> usize {
+        self.data.len().div_ceil(self.stripe_size)
+    }
+
+    /// Returns the length of every shard of every stripe.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::striper::Striper;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let striper = Striper::new(&rs, &[0; 10], 5).unwrap();
+    /// assert_eq!(3, striper.shard_len());
+    /// ```
+    pub fn shard_len(&self) -> usize {
+        self.shard_elem_len
+    }
+
+    /// Encodes the stripe of the given index, or fails with
+    /// `Error::InvalidStripeIndex` if there is no such stripe.
+    /// Returns all the shards of the stripe including all data and parity shards.
+    /// # Arguments
+    ///
+    /// * `index` - Index of the stripe
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::striper::Striper;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = [0, 1, 2, 3, 4, 5, 6];
+    /// let striper = Striper::new(&rs, &data, 4).unwrap();
+    /// let stripe = striper.stripe(1).unwrap();
+    /// assert_eq!(vec![vec![4, 5], vec![6, 0]], stripe[..2]);
+    /// ```
+    pub fn stripe(&self, index: usize) -> Result<Stripe, Error> {
+        if index >= self.stripe_count() {
+            return Err(Error::InvalidStripeIndex(index));
+        }
+        let start = index * self.stripe_size;
+        let end = (start + self.stripe_size).min(self.data.len());
+
+        self.rs.encode(
+            self.rs
+                .split_payload_into(&self.data[start..end], self.shard_elem_len),
+        )
+    }
+
+    /// Returns an iterator over the encoded stripes in order. Every stripe
+    /// is encoded when it is reached, so only one is held at a time.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::striper::Striper;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let striper = Striper::new(&rs, &[0, 1, 2, 3, 4, 5, 6], 4).unwrap();
+    /// for stripe in striper.stripes() {
+    ///     let stripe = stripe.unwrap();
+    /// }
+    /// ```
+    pub fn stripes(&self) -> Stripes<'_, 'a> {
+        Stripes {
+            striper: self,
+            next: 0,
+        }
+    }
+}
+
+/// An iterator over the encoded stripes of a Striper, as returned by
+/// `Striper::stripes`.
+pub struct Stripes<'s, 'a> {
+    striper: &'s Striper<'a>,
+    next: usize,
+}
+
+impl Iterator for Stripes<'_, '_> {
+    type Item = Result<Stripe, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.striper.stripe_count() {
+            return None;
+        }
+        self.next += 1;
+
+        Some(self.striper.stripe(self.next - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.striper.stripe_count() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ReedSolomon {
+    /// Join the data shards of the stripes encoded by a Striper and remove
+    /// the padding of every stripe. None of the shards can be missing, so
+    /// decode the stripes first.
+    /// Returns the original buffer.
+    /// # Arguments
+    ///
+    /// * `stripes` - All shards of every stripe in order.
+    /// * `stripe_size` - No. of bytes of the buffer held by every stripe.
+    /// * `original_len` - Length of the buffer which was striped.
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::striper::Striper;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let data = [0, 1, 2, 3, 4, 5, 6];
+    /// let striper = Striper::new(&rs, &data, 4).unwrap();
+    /// let stripes: Vec<_> = striper.stripes().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(data.to_vec(), rs.join_stripes(&stripes, 4, data.len()).unwrap());
+    /// ```
+    pub fn join_stripes(
+        &self,
+        stripes: &[Stripe],
+        stripe_size: usize,
+        original_len: usize,
+    ) -> Result<Vec<u8>, Error> {
+        if stripe_size == 0 {
+            return Err(Error::ZeroStripeSize);
+        }
+        let capacity = stripes.len().saturating_mul(stripe_size);
+        if original_len > capacity {
+            return Err(Error::DataLenOutOfBounds(original_len, capacity));
+        }
+
+        let mut data = Vec::with_capacity(original_len);
+        for stripe in stripes.iter() {
+            let stripe_len = stripe_size.min(original_len - data.len());
+            data.extend(self.join(stripe, stripe_len)?);
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_striper_round_trip() {
+        let rs = match ReedSolomon::new(3, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let data: Vec<u8> = (0..100).collect();
+        let striper = match Striper::new(&rs, &data, 16) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(7, striper.stripe_count());
+        assert_eq!(6, striper.shard_len());
+        assert_eq!((7, Some(7)), striper.stripes().size_hint());
+
+        let stripes: Vec<Stripe> = match striper.stripes().collect() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        for stripe in stripes.iter() {
+            assert_eq!(5, stripe.len());
+            assert!(stripe.iter().all(|shard| shard.len() == 6));
+            match rs.verify(stripe) {
+                Ok(x) => assert!(x),
+                Err(e) => panic!("{}", e),
+            }
+        }
+        match rs.join_stripes(&stripes, 16, data.len()) {
+            Ok(x) => assert_eq!(data, x),
+            Err(e) => panic!("{}", e),
+        }
+        match rs.join_stripes(&stripes[..6], 16, data.len()) {
+            Err(Error::DataLenOutOfBounds(100, 96)) => {}
+            _ => panic!("Missing stripes must be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_striper_invalid() {
+        let rs = match ReedSolomon::new(2, 2) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(matches!(
+            Striper::new(&rs, &[0; 4], 0),
+            Err(Error::ZeroStripeSize)
+        ));
+        let striper = match Striper::new(&rs, &[0; 4], 4) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(matches!(
+            striper.stripe(1),
+            Err(Error::InvalidStripeIndex(1))
+        ));
+        match Striper::new(&rs, &[], 4) {
+            Ok(x) => assert_eq!(0, x.stripes().count()),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
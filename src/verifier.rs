@@ -0,0 +1,133 @@
+use crate::error::Error;
+use crate::matrix::Matrix;
+use crate::ReedSolomon;
+
+/// A Struct to verify the parity of a large object one stripe at a time,
+/// so that a scrubber only needs to hold one stripe in memory.
+pub struct StreamVerifier {
+    rs: ReedSolomon,
+    parity: Matrix,
+}
+
+impl StreamVerifier {
+    /// Create a new StreamVerifier for the shards of the given code.
+    /// # Arguments
+    ///
+    /// * `rs` - Code the stripes were encoded with
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::verifier::StreamVerifier;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(4, 2).unwrap();
+    /// let verifier = StreamVerifier::new(rs).unwrap();
+    /// ```
+    pub fn new(rs: ReedSolomon) -> Result<StreamVerifier, Error> {
+        // The parity shards are the data shards times the first columns
+        // of the parity-check matrix, even for a non-systematic code.
+        let parity = rs.parity_check_matrix()?.new_sub_matrix(
+            0,
+            rs.parity_shard_count,
+            0,
+            rs.data_shard_count,
+        );
+
+        Ok(StreamVerifier { rs, parity })
+    }
+
+    /// Check whether the parity of a stripe is consistent with its data.
+    /// Returns false if any byte of the parity shards doesn't match.
+    /// # Arguments
+    ///
+    /// * `data` - Data shards of the stripe
+    /// * `parity` - Parity shards of the stripe
+    ///
+    /// # Example
+    /// ```
+    /// use reed_solomon::verifier::StreamVerifier;
+    /// use reed_solomon::ReedSolomon;
+    ///
+    /// let rs = ReedSolomon::new(2, 2).unwrap();
+    /// let verifier = StreamVerifier::new(rs).unwrap();
+    /// let data: [&[u8]; 2] = [&[0, 1, 2], &[3, 4, 5]];
+    /// let parity: [&[u8]; 2] = [&[6, 11, 12], &[5, 14, 11]];
+    /// let consistent = verifier.verify_block(&data, &parity).unwrap();
+    /// ```
+    pub fn verify_block(&self, data: &[&[u8]], parity: &[&[u8]]) -> Result<bool, Error> {
+        if data.len() != self.rs.data_shard_count || parity.len() != self.rs.parity_shard_count {
+            return Err(Error::WrongNoOfShards);
+        }
+        let shard_elem_len = data[0].len();
+        if shard_elem_len == 0 {
+            return Err(Error::EmptyShards);
+        }
+        if data
+            .iter()
+            .chain(parity.iter())
+            .any(|shard| shard.len() != shard_elem_len)
+        {
+            return Err(Error::InconsistentShards);
+        }
+
+        let mut expected: Vec<u8> = vec![0; shard_elem_len];
+        for (row, &shard) in self.parity.data.iter().zip(parity.iter()) {
+            self.rs.combine_row(row, data, &mut expected);
+            if expected[..] != shard[..] {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReedSolomonBuilder;
+
+    #[test]
+    fn test_verify_block() {
+        for systematic in [true, false] {
+            let rs = match ReedSolomonBuilder::new(3, 2).systematic(systematic).build() {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            let mut blocks: Vec<Vec<Vec<u8>>> = vec![];
+            for b in 0..4 {
+                let mut shards: Vec<Vec<u8>> = (0..3)
+                    .map(|i| (0..8).map(|j| (b * 31 + i * 8 + j) as u8).collect())
+                    .collect();
+                shards.resize(5, vec![0; 8]);
+                match rs.encode(shards) {
+                    Ok(x) => blocks.push(x),
+                    Err(e) => panic!("{}", e),
+                }
+            }
+            blocks[2][4][5] ^= 0x01;
+
+            let verifier = match StreamVerifier::new(rs) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
+            for (b, block) in blocks.iter().enumerate() {
+                let shards: Vec<&[u8]> = block.iter().map(|shard| &shard[..]).collect();
+                match verifier.verify_block(&shards[..3], &shards[3..]) {
+                    Ok(x) => assert_eq!(b != 2, x),
+                    Err(e) => panic!("{}", e),
+                }
+            }
+
+            let shards: Vec<&[u8]> = blocks[0].iter().map(|shard| &shard[..]).collect();
+            assert!(matches!(
+                verifier.verify_block(&shards[..2], &shards[3..]),
+                Err(Error::WrongNoOfShards)
+            ));
+            assert!(matches!(
+                verifier.verify_block(&shards[..3], &[shards[3], &shards[4][..4]]),
+                Err(Error::InconsistentShards)
+            ));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::galois::GaloisField;
+
+/// Corrects the values of a polynomial of degree less than k evaluated at
+/// the given points, up to (n - k) / 2 of which are wrong, with the
+/// Berlekamp-Welch algorithm. It finds the error locator E of degree e,
+/// which is 0 at the wrong points, and Q = P * E from the n equations
+/// Q(x_i) = y_i * E(x_i), and then P = Q / E.
+/// Returns the corrected values, or None if there are too many errors.
+/// # Arguments
+///
+/// * `gf` - Galois Field where all the arithmetic will take place
+/// * `points` - Distinct points the polynomial was evaluated at
+/// * `values` - Received value at every point
+/// * `k` - No. of coefficients of the polynomial
+///
+/// # Example
+/// ```ignore
+/// use crate::galois::GaloisField;
+/// use crate::welch::welch_decode;
+///
+/// let gf = GaloisField::new();
+/// let corrected = welch_decode(&gf, &[0, 1, 2, 3], &[5, 5, 7, 5], 1);
+/// ```
+pub(crate) fn welch_decode(
+    gf: &GaloisField,
+    points: &[u8],
+    values: &[u8],
+    k: usize,
+) -> Option<Vec<u8>> {
+    let n = points.len();
+    let e = (n - k) / 2;
+    let unknowns = k + 2 * e;
+
+    // The unknowns are the k + e coefficients of Q followed by the e
+    // lower coefficients of E, whose coefficient of x^e is 1. The last
+    // column is y_i * x_i^e.
+    let mut system: Vec<Vec<u8>> = Vec::with_capacity(n);
+    for (&x, &y) in points.iter().zip(values.iter()) {
+        let mut row: Vec<u8> = vec![0; unknowns + 1];
+        let mut power: u8 = 1;
+        for j in 0..k + e {
+            row[j] = power;
+            if j < e {
+                row[k + e + j] = gf.mul(y, power);
+            }
+            power = gf.mul(power, x);
+        }
+        row[unknowns] = gf.mul(y, gf.exp(x, e));
+        system.push(row);
+    }
+    let solution = solve(gf, system, unknowns)?;
+
+    let q = &solution[..k + e];
+    let mut locator = solution[k + e..].to_vec();
+    locator.push(1);
+    let p = divide(gf, q, &locator)?;
+
+    let corrected: Vec<u8> = points.iter().map(|&x| evaluate(gf, &p, x)).collect();
+    let errors = corrected
+        .iter()
+        .zip(values.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+    if errors > e {
+        return None;
+    }
+
+    Some(corrected)
+}
+
+/// Solves the linear system given as rows of coefficients followed by the
+/// right hand side by Gaussian elimination. Free unknowns are set to 0.
+/// Returns None if the system is inconsistent.
+fn solve(gf: &GaloisField, mut system: Vec<Vec<u8>>, unknowns: usize) -> Option<Vec<u8>> {
+    let mut pivots: Vec<usize> = Vec::with_capacity(unknowns);
+    let mut r = 0;
+    for c in 0..unknowns {
+        let pivot = match (r..system.len()).find(|&i| system[i][c] != 0) {
+            Some(x) => x,
+            None => continue,
+        };
+        system.swap(r, pivot);
+        let scale = gf.div(1, system[r][c]);
+        for elem in system[r].iter_mut() {
+            *elem = gf.mul(*elem, scale);
+        }
+        let pivot_row = system[r].clone();
+        for (i, row) in system.iter_mut().enumerate() {
+            let factor = row[c];
+            if i == r || factor == 0 {
+                continue;
+            }
+            for j in c..=unknowns {
+                row[j] ^= gf.mul(factor, pivot_row[j]);
+            }
+        }
+        pivots.push(c);
+        r += 1;
+    }
+    // A row with no pivot left must have a right hand side of 0.
+    if system[r..].iter().any(|row| row[unknowns] != 0) {
+        return None;
+    }
+
+    let mut solution: Vec<u8> = vec![0; unknowns];
+    for (row, &c) in system.iter().zip(pivots.iter()) {
+        solution[c] = row[unknowns];
+    }
+
+    Some(solution)
+}
+
+/// Divides the polynomial by the monic divisor, both given from the lowest
+/// coefficient. Returns None if the remainder is not 0.
+fn divide(gf: &GaloisField, dividend: &[u8], divisor: &[u8]) -> Option<Vec<u8>> {
+    let degree = divisor.len() - 1;
+    let mut remainder = dividend.to_vec();
+    let mut quotient: Vec<u8> = vec![0; dividend.len() - degree];
+    for i in (0..quotient.len()).rev() {
+        let coefficient = remainder[i + degree];
+        quotient[i] = coefficient;
+        for (j, &d) in divisor.iter().enumerate() {
+            remainder[i + j] ^= gf.mul(coefficient, d);
+        }
+    }
+    if remainder.iter().any(|&x| x != 0) {
+        return None;
+    }
+
+    Some(quotient)
+}
+
+/// Evaluates the polynomial given from the lowest coefficient at x.
+fn evaluate(gf: &GaloisField, poly: &[u8], x: u8) -> u8 {
+    poly.iter()
+        .rev()
+        .fold(0, |acc, &coefficient| gf.mul(acc, x) ^ coefficient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_welch_decode() {
+        let gf = GaloisField::new();
+        let points: Vec<u8> = (0..7).collect();
+        // 3 + 5x + 7x^2 evaluated at every point.
+        let values: Vec<u8> = points
+            .iter()
+            .map(|&x| evaluate(&gf, &[3, 5, 7], x))
+            .collect();
+        assert_eq!(Some(values.clone()), welch_decode(&gf, &points, &values, 3));
+
+        let mut received = values.clone();
+        received[1] ^= 0x10;
+        received[5] ^= 0x77;
+        assert_eq!(
+            Some(values.clone()),
+            welch_decode(&gf, &points, &received, 3)
+        );
+
+        received[6] ^= 0x01;
+        assert_ne!(Some(values), welch_decode(&gf, &points, &received, 3));
+    }
+}
@@ -0,0 +1,68 @@
+/// Increment of the SplitMix64 generator (the golden ratio in 64 bits).
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Generate the next value of a SplitMix64 generator and advance its state.
+/// # Arguments
+///
+/// * `state` - State of the generator
+///
+/// # Example
+/// ```ignore
+/// use crate::whiten::split_mix64;
+///
+/// let mut state = 42;
+/// let value = split_mix64(&mut state);
+/// ```
+pub(crate) fn split_mix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(GOLDEN_GAMMA);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// XOR the shard with a keystream derived from the seed and the index
+/// of the shard. Applying it twice with the same seed and index gives
+/// back the original shard. This is whitening, not encryption.
+/// # Arguments
+///
+/// * `shard` - Shard to be whitened or un-whitened in place
+/// * `seed` - Seed of the keystream
+/// * `index` - Index of the shard, so every shard gets a different keystream
+///
+/// # Example
+/// ```ignore
+/// use crate::whiten::whiten;
+///
+/// let mut shard = vec![0, 1, 2];
+/// whiten(&mut shard, 42, 0);
+/// ```
+pub(crate) fn whiten(shard: &mut [u8], seed: u64, index: usize) {
+    let mut state = seed ^ (index as u64).wrapping_mul(GOLDEN_GAMMA);
+    for chunk in shard.chunks_mut(8) {
+        let key = split_mix64(&mut state).to_le_bytes();
+        for (elem, k) in chunk.iter_mut().zip(key.iter()) {
+            *elem ^= k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whiten() {
+        let original: Vec<u8> = (0..20).collect();
+        let mut shard = original.clone();
+        whiten(&mut shard, 42, 0);
+        assert_ne!(original, shard);
+
+        let mut other_shard = original.clone();
+        whiten(&mut other_shard, 42, 1);
+        assert_ne!(shard, other_shard);
+
+        whiten(&mut shard, 42, 0);
+        assert_eq!(original, shard);
+    }
+}